@@ -1,5 +1,4 @@
 use brickset::v3::{request::OrderBy, reqwest_api::ClientWrapper, response::LegoComDetails};
-use dotenv;
 use std::{env, fs::File, io::Write};
 
 #[tokio::main]
@@ -37,10 +36,9 @@ async fn main() {
         println!("  Pieces: {}", set.pieces.map_or_else(|| unknown.clone(), |p| p.to_string()));
         println!("  Availability: {}", set.availability.as_ref().unwrap_or(&unknown));
         println!("  Pricing on LEGO.com:");
-        print_pricing("USD", &set.lego_com.united_states);
-        print_pricing("CAD", &set.lego_com.canada);
-        print_pricing("EUR", &set.lego_com.germany);
-        print_pricing("GBP", &set.lego_com.united_kingdom);
+        for (region, details) in set.lego_com.iter() {
+            print_pricing(region.currency_code(), details);
+        }
     }
 }
 
@@ -50,7 +48,7 @@ fn print_pricing(tag: &str, details: &LegoComDetails) {
         if let Some(last_available) = details.date_last_available {
             print!(" Last available: {}", last_available.format("%Y-%m-%d"));
         }
-        println!("");
+        println!();
     }
 }
 
@@ -59,7 +57,7 @@ async fn log_into_brickset<'a>(client: &mut ClientWrapper<'a>, username: &str) {
 
     // try to log in using cached token
     if dotenv::from_filename(".env.examples.generated").is_ok() {
-        if let Some(user_hash) = env::var("BRICKSET_USER_HASH").ok() {
+        if let Ok(user_hash) = env::var("BRICKSET_USER_HASH") {
             match client.reuse_login(&user_hash).await {
                 Ok(_) => println!("Logged in using cached token"),
                 Err(err) => println!("Could not log in with cached token: {err}"),
@@ -71,7 +69,7 @@ async fn log_into_brickset<'a>(client: &mut ClientWrapper<'a>, username: &str) {
         // couldn't log in using cached token, so ask for a password
         println!("Username: {username}");
         let password = rpassword::prompt_password("Password: ").unwrap();
-        let login = client.log_in(&username, &password).await.expect("log_in");
+        let login = client.log_in(username, &password).await.expect("log_in");
 
         println!("Successfully logged in");
 
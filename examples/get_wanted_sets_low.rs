@@ -1,7 +1,6 @@
 use brickset::v3::Response;
 use brickset::v3::response::{CheckUserHashResponse, LegoComDetails, LoginResponse, GetSetsResponse};
 use brickset::v3::request::{OrderBy, CheckUserHash, BricksetRequest, ENDPOINT, Login, GetSets, GetSetsParameters};
-use dotenv;
 use reqwest::Client;
 use std::{env, fs::File, io::Write};
 
@@ -36,7 +35,7 @@ async fn main() {
     // create a reqwest::Request
     let request = client.post(dest)
         .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .header(reqwest::header::CONTENT_LENGTH, body.as_bytes().len())
+        .header(reqwest::header::CONTENT_LENGTH, body.len())
         .body(body)
         .build()
         .expect("building request");
@@ -68,10 +67,9 @@ async fn main() {
         println!("  Pieces: {}", set.pieces.map_or_else(|| unknown.clone(), |p| p.to_string()));
         println!("  Availability: {}", set.availability.as_ref().unwrap_or(&unknown));
         println!("  Pricing on LEGO.com:");
-        print_pricing("USD", &set.lego_com.united_states);
-        print_pricing("CAD", &set.lego_com.canada);
-        print_pricing("EUR", &set.lego_com.germany);
-        print_pricing("GBP", &set.lego_com.united_kingdom);
+        for (region, details) in set.lego_com.iter() {
+            print_pricing(region.currency_code(), details);
+        }
     }
 }
 
@@ -81,7 +79,7 @@ fn print_pricing(tag: &str, details: &LegoComDetails) {
         if let Some(last_available) = details.date_last_available {
             print!(" Last available: {}", last_available.format("%Y-%m-%d"));
         }
-        println!("");
+        println!();
     }
 }
 
@@ -90,7 +88,7 @@ async fn log_into_brickset(client: &mut Client, api_key: &str, username: &str) -
 
     // try to log in using cached token
     if dotenv::from_filename(".env.examples.generated").is_ok() {
-        if let Some(user_hash) = env::var("BRICKSET_USER_HASH").ok() {
+        if let Ok(user_hash) = env::var("BRICKSET_USER_HASH") {
             // build the checkUserHash request URI and body
             let builder = CheckUserHash::new(api_key, &user_hash);
             let dest = ENDPOINT.join(builder.method_name()).expect("encoding url");
@@ -101,7 +99,7 @@ async fn log_into_brickset(client: &mut Client, api_key: &str, username: &str) -
             // create a reqwest::Request
             let request = client.post(dest)
                 .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-                .header(reqwest::header::CONTENT_LENGTH, body.as_bytes().len())
+                .header(reqwest::header::CONTENT_LENGTH, body.len())
                 .body(body)
                 .build()
                 .expect("building request");
@@ -141,7 +139,7 @@ async fn log_into_brickset(client: &mut Client, api_key: &str, username: &str) -
     // create a reqwest::Request
     let request = client.post(dest)
         .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
-        .header(reqwest::header::CONTENT_LENGTH, body.as_bytes().len())
+        .header(reqwest::header::CONTENT_LENGTH, body.len())
         .body(body)
         .build()
         .expect("building request");
@@ -171,7 +169,7 @@ async fn log_into_brickset(client: &mut Client, api_key: &str, username: &str) -
                     .expect("write_fmt");
             }
 
-            return hash;
+            hash
         }
         Response::Err(err) => {
             println!("Could not log in: {err}");
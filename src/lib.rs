@@ -6,7 +6,10 @@
 //! 
 //! # Sample
 //! 
-//! ```no_run
+//! This sample requires the `reqwest` feature.
+//! 
+#![cfg_attr(feature = "reqwest", doc = "```no_run")]
+#![cfg_attr(not(feature = "reqwest"), doc = "```ignore")]
 //! use brickset::{reqwest_api::ClientWrapper, request::GetSetsParameters};
 //! use std::error::Error;
 //! 
@@ -34,7 +37,28 @@
 //! 
 //! - `log` (default): Generate log messages using the [log](https://docs.rs/log/) crate.
 //! - `reqwest` (default): High-level wrapper for [reqwest](https://docs.rs/reqwest/). If
-//!   you aren't using reqwest, you should disable this feature.
+//!   you aren't using reqwest, you should disable this feature. Without it, the crate
+//!   doesn't depend on reqwest at all, and requests can still be built with
+//!   `BricksetRequest::encode_query` or `BricksetRequest::to_request_url`.
+//!   With `reqwest` disabled, the crate builds for `wasm32-unknown-unknown`, e.g. to build
+//!   requests in a browser and send them with `fetch`:
+//!   `cargo build --target wasm32-unknown-unknown --no-default-features`.
+//! - `blocking`: Blocking version of the high-level wrapper, using `reqwest::blocking`.
+//!   Implies `reqwest`, so the async and blocking wrappers can be used side by side.
+//! - `gzip`, `deflate`: Ask BrickSet for compressed responses, and decompress them. This
+//!   is worthwhile for large `getSets` responses. Implies `reqwest`, and enables the
+//!   corresponding reqwest feature. If you build your own requests, make sure the
+//!   `reqwest::Client` you use can decompress the response.
+//! - `strict-parsing`: Fail to parse responses that contain fields this crate doesn't know
+//!   about. This is meant for detecting changes to the BrickSet API, e.g. by running tests
+//!   against recorded responses, and shouldn't be enabled in normal use.
+//! - `tracing`: Wrap each request sent by the high-level wrappers in a
+//!   [tracing](https://docs.rs/tracing/) span, recording the method name, HTTP status code,
+//!   and response size. This is independent of the `log` feature.
+//! - `test-fixtures`: Sample BrickSet responses in `response::fixtures`, for testing code
+//!   that handles BrickSet data without a network connection.
+//! - `record`: Transports in `reqwest_api::record` that save BrickSet responses to files and
+//!   play them back, for keeping test fixtures up to date. Implies `reqwest`.
 
 pub mod v3;
 
@@ -51,6 +51,33 @@ impl<T> Response<T> {
         }
     }
 
+    /// Convert to an [`Option<T>`], discarding the error.
+    #[inline]
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Response::Ok(ok) => Some(ok),
+            Response::Err(_) => None,
+        }
+    }
+
+    /// Transform the success value of a [`Response`], leaving errors untouched.
+    #[inline]
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Response<U> {
+        match self {
+            Response::Ok(ok) => Response::Ok(f(ok)),
+            Response::Err(err) => Response::Err(err),
+        }
+    }
+
+    /// Call `f` with the success value of a [`Response`], leaving errors untouched.
+    #[inline]
+    pub fn and_then<U, F: FnOnce(T) -> Response<U>>(self, f: F) -> Response<U> {
+        match self {
+            Response::Ok(ok) => f(ok),
+            Response::Err(err) => Response::Err(err),
+        }
+    }
+
     #[inline]
     pub fn unwrap(self) -> T {
         match self {
@@ -79,6 +106,26 @@ impl<T> From<Response<T>> for std::result::Result<T,response::Error> {
     }
 }
 
+/// Iterates over the success value of a [`Response`], like [`Option`]. Yields one item for
+/// [`Response::Ok`], and nothing for [`Response::Err`].
+impl<T> IntoIterator for Response<T> {
+    type Item = T;
+    type IntoIter = std::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ok().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Response<T> {
+    type Item = &'a T;
+    type IntoIter = std::option::IntoIter<&'a T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.get().into_iter()
+    }
+}
+
 
 #[cfg(test)]
 mod response_tests {
@@ -87,6 +134,50 @@ mod response_tests {
     use serde::Deserialize;
     use serde_json::Deserializer;
 
+    pub(super) const SET: &str = r#" {
+        "setID": 31278,
+        "number": "10497",
+        "numberVariant": 1,
+        "name": "Galaxy Explorer",
+        "year": 2022,
+        "theme": "Icons",
+        "themeGroup": "Model making",
+        "subtheme": "Space",
+        "category": "Normal",
+        "released": true,
+        "pieces": 1254,
+        "minifigs": 4,
+        "image": {
+            "thumbnailURL": "https://images.brickset.com/sets/small/10497-1.jpg",
+            "imageURL": "https://images.brickset.com/sets/images/10497-1.jpg"
+        },
+        "bricksetURL": "https://brickset.com/sets/10497-1",
+        "collection": {},
+        "collections": { "ownedBy": 21523, "wantedBy": 6388 },
+        "LEGOCom": {
+            "US": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+            "UK": { "retailPrice": 89.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+            "CA": { "retailPrice": 129.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+            "DE": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+            "FR": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" }
+        },
+        "rating": 4.6,
+        "reviewCount": 12,
+        "packagingType": "Box",
+        "availability": "LEGO exclusive",
+        "instructionsCount": 2,
+        "additionalImageCount": 14,
+        "ageRange": { "min": 18 },
+        "dimensions": { "height": 37.8, "width": 47.9, "depth": 9.4, "weight": 1.945 },
+        "barcode": { "EAN": "5702017152738", "UPC": "673419363741" },
+        "extendedData": { "tags": ["Spaceship", "Classic Space", "D2C"] },
+        "lastUpdated": "2023-06-01T09:13:44.553Z"
+    } "#;
+
+    fn set() -> Set {
+        serde_json::from_str(SET).expect("from_str")
+    }
+
     #[test]
     fn check_key_success() {
         let input = r#" {"status":"success"} "#;
@@ -104,6 +195,589 @@ mod response_tests {
         let err = resp.unwrap_err();
         assert_eq!(err.message, "Invalid API key");
     }
+
+    #[test]
+    fn combinators() {
+        let ok = Response::<i32>::Ok(2);
+        let err = Response::<i32>::Err(Error { message: "Invalid API key".to_string() });
+
+        assert_eq!(ok.clone().map(|x| x * 2).ok(), Some(4));
+        assert_eq!(err.clone().map(|x| x * 2).unwrap_err().message, "Invalid API key");
+
+        assert_eq!(ok.clone().and_then(|x| Response::Ok(x + 1)).ok(), Some(3));
+        assert!(ok.clone().and_then(|_| Response::<i32>::Err(Error { message: "nope".to_string() })).ok().is_none());
+        assert_eq!(err.clone().and_then(|x| Response::Ok(x + 1)).unwrap_err().message, "Invalid API key");
+
+        assert_eq!(ok.ok(), Some(2));
+        assert_eq!(err.ok(), None);
+    }
+
+    #[test]
+    fn into_iter() {
+        let responses = vec![
+            Response::<i32>::Ok(1),
+            Response::Err(Error { message: "Invalid API key".to_string() }),
+            Response::Ok(3),
+        ];
+        assert_eq!((&responses[0]).into_iter().collect::<Vec<_>>(), [&1]);
+        assert_eq!(responses.into_iter().flatten().collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        let input = r#" {"status":"success","matches":0,"themes":[],"newField":true} "#;
+        let result = serde_json::from_str::<Response<GetThemesResponse>>(input);
+        assert_eq!(result.is_err(), cfg!(feature = "strict-parsing"));
+
+        let mut set: serde_json::Value = serde_json::from_str(SET).expect("from_str");
+        set["newField"] = "value".into();
+        assert_eq!(serde_json::from_value::<Set>(set).is_err(), cfg!(feature = "strict-parsing"));
+    }
+
+    #[test]
+    fn key_usage_stats() {
+        let input = r#" {"status":"success","matches":3,"apiKeyUsage":[
+            {"dateStamp":"2023-06-02T00:00:00Z","count":12},
+            {"dateStamp":"2023-06-01T00:00:00Z","count":30},
+            {"dateStamp":"2023-06-01T14:00:00Z","count":5}
+        ]} "#;
+        let usage = serde_json::from_str::<Response<GetKeyUsageStatsResponse>>(input).expect("from_str").unwrap();
+        assert_eq!(usage.total(), 47);
+        assert_eq!(usage.on(chrono::NaiveDate::from_ymd_opt(2023, 6, 1).unwrap()), 35);
+        assert_eq!(usage.on(chrono::NaiveDate::from_ymd_opt(2023, 6, 3).unwrap()), 0);
+        assert_eq!(usage.today(), 0);
+    }
+
+    #[cfg(feature = "test-fixtures")]
+    #[test]
+    fn fixtures() {
+        let sets = fixtures::example_get_sets();
+        assert_eq!(sets.matches, 2);
+        assert_eq!(sets.sets[0].name.as_deref(), Some("Galaxy Explorer"));
+        assert_eq!(sets.sets[1].subtheme, None);
+        assert_eq!(sets.sets[1].rating, None);
+
+        let themes = fixtures::example_get_themes();
+        assert_eq!(themes.find("space").map(|theme| theme.set_count), Some(812));
+
+        assert_eq!(fixtures::example_error().message, "Invalid API key");
+    }
+
+    #[test]
+    fn page_count() {
+        let response = GetSetsResponse { matches: 41, sets: vec![] };
+        assert_eq!(response.page_count(20), 3);
+        assert_eq!(response.page_count(41), 1);
+        assert_eq!(response.page_count(0), 0);
+        assert!(response.has_more(2, 20));
+        assert!(!response.has_more(3, 20));
+        assert!(!response.has_more(1, 0));
+
+        let response = GetSetsResponse { matches: 0, sets: vec![] };
+        assert_eq!(response.page_count(20), 0);
+        assert!(!response.has_more(1, 20));
+    }
+
+    #[test]
+    fn find_themes() {
+        let input = r#" {"status":"success","matches":2,"themes":[
+            {"theme":"Space","setCount":812,"subthemeCount":31,"yearFrom":1978,"yearTo":2023},
+            {"theme":"Town","setCount":1032,"subthemeCount":40,"yearFrom":1978,"yearTo":2004}
+        ]} "#;
+        let themes = serde_json::from_str::<Response<GetThemesResponse>>(input).expect("from_str").unwrap();
+        assert_eq!(themes.find("space").map(|t| t.set_count), Some(812));
+        assert!(themes.find("Castle").is_none());
+        let map = themes.into_map();
+        assert_eq!(map["Town"].year_to, 2004);
+
+        let input = r#" {"status":"success","matches":1,"subthemes":[
+            {"theme":"Space","subtheme":"Blacktron","setCount":12,"yearFrom":1987,"yearTo":1988}
+        ]} "#;
+        let subthemes = serde_json::from_str::<Response<GetSubthemesResponse>>(input).expect("from_str").unwrap();
+        assert_eq!(subthemes.find("BLACKTRON").map(|s| s.year_from), Some(1987));
+        assert!(subthemes.find("M-Tron").is_none());
+
+        let input = r#" {"status":"success","matches":2,"years":[
+            {"theme":"Space","year":1987,"setCount":14},
+            {"theme":"Space","year":1988,"setCount":9}
+        ]} "#;
+        let years = serde_json::from_str::<Response<GetYearsResponse>>(input).expect("from_str").unwrap();
+        assert_eq!(years.find_year(1988).map(|y| y.set_count), Some(9));
+        assert!(years.find_year(1989).is_none());
+        assert_eq!(years.earliest(), Some(1987));
+        assert_eq!(years.latest(), Some(1988));
+        assert_eq!(years.total_sets(), 23);
+
+        let empty = GetYearsResponse { matches: 0, years: vec![] };
+        assert_eq!(empty.earliest(), None);
+        assert_eq!(empty.latest(), None);
+        assert_eq!(empty.total_sets(), 0);
+    }
+
+    #[test]
+    fn error_kind() {
+        let kind = |message: &str| Error { message: message.to_string() }.kind();
+        assert_eq!(kind("Invalid API key"), ApiErrorKind::InvalidApiKey);
+        assert_eq!(kind("Invalid userHash"), ApiErrorKind::InvalidUserHash);
+        assert_eq!(kind("Invalid user hash"), ApiErrorKind::InvalidUserHash);
+        assert_eq!(kind("Daily request limit exceeded"), ApiErrorKind::RateLimited);
+        assert_eq!(kind("API key daily request limit exceeded"), ApiErrorKind::RateLimited);
+        assert_eq!(kind("Invalid API key: limit exceeded"), ApiErrorKind::RateLimited);
+        assert_eq!(kind("User hash request limit reached"), ApiErrorKind::RateLimited);
+        assert_eq!(kind("API key is missing"), ApiErrorKind::Other);
+        assert_eq!(kind("Limited edition sets only"), ApiErrorKind::Other);
+        assert_eq!(kind("No set found"), ApiErrorKind::Other);
+    }
+
+    #[test]
+    fn similar_query() {
+        let mut set = set();
+        set.subtheme = Some("Galaxy Explorer".to_string());
+        let params = set.similar_query().order_by(super::request::OrderBy::YearFrom);
+        assert_eq!(params.get_themes(), &["Icons"]);
+        assert_eq!(params.get_subtheme(), Some("Galaxy Explorer"));
+        assert_eq!(params.get_order_by(), Some(super::request::OrderBy::YearFrom));
+
+        set.theme = None;
+        set.subtheme = None;
+        assert_eq!(set.similar_query().to_params_json().expect("to_params_json"), "{}");
+    }
+
+    #[test]
+    fn collection_id() {
+        assert_eq!(set().collection_id(), None);
+
+        let owned = SET.replace(r#""collection": {}"#, r#""collection": { "owned": true, "qtyOwned": 1, "collectionID": 987654 }"#);
+        let owned: Set = serde_json::from_str(&owned).expect("from_str");
+        assert_eq!(owned.collection_id(), Some(987654));
+    }
+
+    #[test]
+    fn ownership_accessors() {
+        let set = set();
+        assert!(!set.is_owned());
+        assert!(!set.is_wanted());
+        assert_eq!(set.qty_owned(), 0);
+
+        let owned = SET.replace(r#""collection": {}"#, r#""collection": { "owned": true, "wanted": false, "qtyOwned": 2 }"#);
+        let owned: Set = serde_json::from_str(&owned).expect("from_str");
+        assert!(owned.is_owned());
+        assert!(!owned.is_wanted());
+        assert_eq!(owned.qty_owned(), 2);
+        assert_eq!(owned.collection.is_wanted, Some(false));
+
+        let wanted = SET.replace(r#""collection": {}"#, r#""collection": { "wanted": true }"#);
+        let wanted: Set = serde_json::from_str(&wanted).expect("from_str");
+        assert!(!wanted.is_owned());
+        assert!(wanted.is_wanted());
+        assert_eq!(wanted.qty_owned(), 0);
+        assert_eq!(wanted.collection.is_owned, None);
+    }
+
+    #[test]
+    fn list_response_iterators() {
+        let mut other = set();
+        other.set_id = 1;
+        let response = GetSetsResponse { matches: 10, sets: vec![set(), other] };
+        assert_eq!(response.len(), 2);
+        assert!(!response.is_empty());
+
+        let ids: Vec<u64> = response.iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278, 1]);
+        let ids: Vec<u64> = (&response).into_iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278, 1]);
+        let ids: Vec<u64> = response.clone().into_iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278, 1]);
+        assert_eq!(response.into_sets().len(), 2);
+
+        let themes = GetThemesResponse { matches: 0, themes: vec![] };
+        assert!(themes.is_empty());
+        assert_eq!((&themes).into_iter().count(), 0);
+    }
+
+    #[test]
+    fn year_span() {
+        let themes: GetThemesResponse = serde_json::from_str(r#"{"matches":2,"themes":[
+            {"theme":"Space","setCount":812,"subthemeCount":31,"yearFrom":1978,"yearTo":2023},
+            {"theme":"Town","setCount":1032,"subthemeCount":40,"yearFrom":1978,"yearTo":2004}
+        ]}"#).expect("from_str");
+        assert_eq!(themes.themes[1].year_span(), 1978..=2004);
+        let active: Vec<&str> = themes.iter().filter(|t| t.is_active(2010)).map(|t| t.name.as_str()).collect();
+        assert_eq!(active, ["Space"]);
+        assert!(themes.themes[1].is_active(1978));
+        assert!(themes.themes[1].is_active(2004));
+        assert!(!themes.themes[1].is_active(1977));
+
+        let subtheme: Subtheme = serde_json::from_str(r#"{"theme":"Space","subtheme":"Blacktron","setCount":12,"yearFrom":1987,"yearTo":1988}"#).expect("from_str");
+        assert_eq!(subtheme.year_span(), 1987..=1988);
+        assert!(!subtheme.is_active(1989));
+    }
+
+    #[test]
+    fn with_pieces_and_minifigs() {
+        let mut no_pieces = set();
+        no_pieces.set_id = 1;
+        no_pieces.pieces = None;
+        let mut no_minifigs = set();
+        no_minifigs.set_id = 2;
+        no_minifigs.minifigs = None;
+        let response = GetSetsResponse { matches: 3, sets: vec![set(), no_pieces, no_minifigs] };
+
+        let ids: Vec<u64> = response.with_pieces().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278, 2]);
+        let ids: Vec<u64> = response.with_minifigs().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278, 1]);
+    }
+
+    #[test]
+    fn group_by_theme_and_year() {
+        let mut castle = set();
+        castle.set_id = 1;
+        castle.theme = Some("Castle".to_string());
+        castle.year = 1984;
+        let mut unknown = set();
+        unknown.set_id = 2;
+        unknown.theme = None;
+        let response = GetSetsResponse { matches: 3, sets: vec![set(), castle, unknown] };
+
+        let themes = response.group_by_theme();
+        let ids: Vec<(&str, Vec<u64>)> = themes.iter()
+            .map(|(theme, sets)| (theme.as_str(), sets.iter().map(|set| set.set_id).collect()))
+            .collect();
+        assert_eq!(ids, [("Castle", vec![1]), ("Icons", vec![31278]), ("Unknown", vec![2])]);
+
+        let years = response.group_by_year();
+        let ids: Vec<(i32, Vec<u64>)> = years.iter()
+            .map(|(year, sets)| (*year, sets.iter().map(|set| set.set_id).collect()))
+            .collect();
+        assert_eq!(ids, [(1984, vec![1]), (2022, vec![31278, 2])]);
+    }
+
+    #[test]
+    fn filter_by_any_tag() {
+        let mut untagged = set();
+        untagged.set_id = 1;
+        untagged.extended_data.tags.clear();
+        let response = GetSetsResponse { matches: 2, sets: vec![set(), untagged] };
+
+        let ids: Vec<u64> = response.filter_by_any_tag(&["castle", "d2c"]).iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [31278]);
+        assert!(response.filter_by_any_tag(&["Castle"]).is_empty());
+        assert!(response.filter_by_any_tag(&[]).is_empty());
+    }
+
+    #[test]
+    fn set_prices() {
+        let mut set = set();
+        assert_eq!(set.retail_price(Region::UK), Some(89.99));
+        assert_eq!(set.price_per_piece(Region::US), Some(99.99 / 1254.0));
+        set.pieces = Some(0);
+        assert_eq!(set.price_per_piece(Region::US), None);
+        set.pieces = None;
+        assert_eq!(set.price_per_piece(Region::US), None);
+    }
+
+    #[test]
+    fn age_range_display() {
+        let age_range = |min, max| AgeRange { min, max };
+        assert_eq!(age_range(Some(6.0), Some(12.0)).display().as_deref(), Some("6-12"));
+        assert_eq!(age_range(Some(18.0), None).display().as_deref(), Some("18+"));
+        assert_eq!(age_range(None, Some(12.0)).display().as_deref(), Some("up to 12"));
+        assert_eq!(age_range(Some(1.5), Some(5.0)).display().as_deref(), Some("1.5-5"));
+        assert_eq!(age_range(None, None).display(), None);
+        assert_eq!(set().age_range.display().as_deref(), Some("18+"));
+    }
+
+    #[test]
+    fn dimensions() {
+        let dimensions = Dimensions { height: Some(25.4), width: Some(50.8), depth: Some(2.54), weight: Some(2.0) };
+        let imperial = dimensions.to_imperial();
+        assert!((imperial.height.unwrap() - 10.0).abs() < 1e-9);
+        assert!((imperial.width.unwrap() - 20.0).abs() < 1e-9);
+        assert!((imperial.depth.unwrap() - 1.0).abs() < 1e-9);
+        assert!((imperial.weight.unwrap() - 70.5479239).abs() < 1e-6);
+        assert!((dimensions.volume_cm3().unwrap() - 3277.412).abs() < 1e-3);
+
+        let dimensions = Dimensions { height: Some(25.4), width: None, depth: Some(2.54), weight: None };
+        assert_eq!(dimensions.to_imperial().width, None);
+        assert_eq!(dimensions.to_imperial().weight, None);
+        assert_eq!(dimensions.volume_cm3(), None);
+
+        // The sample set weighs 1.945 kg, or about 68.6 oz
+        let weight = set().dimensions.to_imperial().weight.unwrap();
+        assert!((weight - 68.6079).abs() < 1e-3);
+    }
+
+    #[test]
+    fn barcode() {
+        let barcode = |upc: &str, ean: &str| Barcode { upc: Some(upc.to_string()), ean: Some(ean.to_string()) };
+        let valid = barcode(" 0 36000 29145 2 ", "4006381-333931");
+        assert_eq!(valid.normalized_upc().as_deref(), Some("036000291452"));
+        assert_eq!(valid.normalized_ean().as_deref(), Some("4006381333931"));
+
+        let bad_check_digit = barcode("036000291453", "4006381333932");
+        assert_eq!(bad_check_digit.normalized_upc(), None);
+        assert_eq!(bad_check_digit.normalized_ean(), None);
+
+        let wrong_length = barcode("4006381333931", "036000291452");
+        assert_eq!(wrong_length.normalized_upc(), None);
+        assert_eq!(wrong_length.normalized_ean(), None);
+
+        assert_eq!(Barcode { upc: None, ean: None }.normalized_upc(), None);
+    }
+
+    #[test]
+    fn set_id() {
+        let set = set();
+        assert_eq!(set.id(), super::request::SetId(31278));
+        assert_eq!(u64::from(set.id()), 31278);
+        assert_eq!(set.id().to_string(), "31278");
+
+        let mut other = set.clone();
+        other.name = Some("Galaxy Explorer (duplicate)".to_string());
+        let ids: std::collections::HashSet<_> = [&set, &other].iter().map(|set| set.id()).collect();
+        assert_eq!(ids.len(), 1);
+        assert_eq!(serde_json::to_string(&set.id()).unwrap(), "31278");
+    }
+
+    #[test]
+    fn string_numbers() {
+        let mut set: serde_json::Value = serde_json::from_str(SET).expect("from_str");
+        set["LEGOCom"]["US"]["retailPrice"] = "99.99".into();
+        set["rating"] = "4.6".into();
+        set["ageRange"]["min"] = "18".into();
+        set["dimensions"]["weight"] = "1.2".into();
+        let set: Set = serde_json::from_value(set).expect("from_value");
+        assert_eq!(set.retail_price(Region::US), Some(99.99));
+        assert_eq!(set.rating, Some(4.6));
+        assert_eq!(set.age_range.min, Some(18.0));
+        assert_eq!(set.dimensions.weight, Some(1.2));
+
+        let mut set: serde_json::Value = serde_json::from_str(SET).expect("from_str");
+        set["rating"] = "0".into();
+        let set: Set = serde_json::from_value(set).expect("from_value");
+        assert_eq!(set.rating, None);
+    }
+
+    #[test]
+    fn not_specified() {
+        let mut set: serde_json::Value = serde_json::from_str(SET).expect("from_str");
+        for field in ["name", "theme", "themeGroup", "subtheme", "category", "packagingType", "availability"] {
+            set[field] = "{Not specified}".into();
+        }
+        set["rating"] = 0.into();
+        let set: Set = serde_json::from_value(set).expect("from_value");
+        assert_eq!(set.name, None);
+        assert_eq!(set.theme, None);
+        assert_eq!(set.theme_group, None);
+        assert_eq!(set.subtheme, None);
+        assert_eq!(set.category, None);
+        assert_eq!(set.packaging_type, None);
+        assert_eq!(set.availability, None);
+        assert_eq!(set.rating, None);
+
+        let mut set: serde_json::Value = serde_json::from_str(SET).expect("from_str");
+        set.as_object_mut().unwrap().remove("packagingType");
+        set.as_object_mut().unwrap().remove("availability");
+        let set: Set = serde_json::from_value(set).expect("from_value");
+        assert_eq!(set.packaging_type, None);
+        assert_eq!(set.rating, Some(4.6));
+    }
+
+    #[test]
+    fn has_tag() {
+        let set = set();
+        assert!(set.has_tag("classic space"));
+        assert!(set.has_tag("D2C"));
+        assert!(!set.has_tag("Space"));
+        assert!(!set.has_tag(""));
+    }
+
+    #[test]
+    fn collections() {
+        let collections = Collections { owned_by: Some(4000), wanted_by: Some(1000) };
+        assert_eq!(collections.want_to_own_ratio(), Some(0.25));
+        assert_eq!(collections.popularity(), Some(5000));
+
+        let collections = Collections { owned_by: Some(0), wanted_by: Some(12) };
+        assert_eq!(collections.want_to_own_ratio(), None);
+        assert_eq!(collections.popularity(), Some(12));
+
+        let collections = Collections { owned_by: Some(30), wanted_by: None };
+        assert_eq!(collections.want_to_own_ratio(), None);
+        assert_eq!(collections.popularity(), None);
+    }
+
+    #[test]
+    fn rating() {
+        let rating: Rating = serde_json::from_str(r#" {"overall":4,"parts":5,"buildingExperience":0,"playability":3,"valueForMoney":0} "#).expect("from_str");
+        assert_eq!(rating.building_experience, None);
+        assert!(!rating.is_complete());
+        assert_eq!(rating.average(), Some(4.0));
+
+        let rating: Rating = serde_json::from_str(r#" {"overall":5,"parts":5,"buildingExperience":4,"playability":3,"valueForMoney":4} "#).expect("from_str");
+        assert!(rating.is_complete());
+        assert_eq!(rating.average(), Some(4.0));
+
+        let rating: Rating = serde_json::from_str(r#" {"overall":0,"parts":0,"buildingExperience":0,"playability":0,"valueForMoney":0} "#).expect("from_str");
+        assert!(!rating.is_complete());
+        assert_eq!(rating.average(), None);
+    }
+
+    #[test]
+    fn review_stats() {
+        let review = |overall: i32| format!(r#"{{"author":"someone","datePosted":"2023-01-01T00:00:00Z","rating":{{"overall":{overall},"parts":0,"buildingExperience":0,"playability":0,"valueForMoney":0}},"title":"","review":"","HTML":false}}"#);
+        let input = format!(r#" {{"status":"success","matches":4,"reviews":[{},{},{},{}]}} "#, review(5), review(4), review(0), review(4));
+        let reviews = serde_json::from_str::<Response<GetReviewsResponse>>(&input).expect("from_str").unwrap();
+        assert_eq!(reviews.average_overall(), Some(13.0 / 3.0));
+        assert_eq!(reviews.rating_histogram(), [1, 0, 0, 0, 2, 1]);
+
+        let reviews = GetReviewsResponse { matches: 0, reviews: vec![] };
+        assert_eq!(reviews.average_overall(), None);
+        assert_eq!(reviews.rating_histogram(), [0; 6]);
+    }
+
+    #[test]
+    fn image() {
+        let image = set().image;
+        assert_eq!(image.best(), image.image_url.as_deref());
+        assert!(image.image().expect("image").is_ok());
+        assert!(image.thumbnail().expect("thumbnail").is_ok());
+
+        let image = Image { thumbnail_url: Some("https://images.brickset.com/sets/thumbs/6876-1.jpg".to_string()), image_url: None };
+        assert_eq!(image.best(), Some("https://images.brickset.com/sets/thumbs/6876-1.jpg"));
+        assert!(image.image().is_none());
+
+        let image = Image { thumbnail_url: Some("not a url".to_string()), image_url: None };
+        assert!(image.thumbnail().expect("thumbnail").is_err());
+        assert_eq!(Image { thumbnail_url: None, image_url: None }.best(), None);
+    }
+
+    #[test]
+    fn instructions_dedup_and_sort() {
+        let input = r#" {"status":"success","matches":4,"instructions":[
+            {"URL":"https://example.com/b.pdf","description":"BI 3006, 2/2"},
+            {"URL":"https://example.com/a.pdf","description":"BI 3006, 1/2"},
+            {"URL":"https://example.com/b.pdf","description":"BI 3006, 2/2 (copy)"},
+            {"URL":"https://example.com/c.pdf","description":"BI 3006, 1/2"}
+        ]} "#;
+        let instructions = serde_json::from_str::<Response<GetInstructionsResponse>>(input).expect("from_str").unwrap();
+        let urls = |list: Vec<&Instructions>| list.iter().map(|i| i.url.rsplit('/').next().unwrap().to_string()).collect::<Vec<_>>();
+        assert_eq!(urls(instructions.deduped()), ["b.pdf", "a.pdf", "c.pdf"]);
+        assert_eq!(urls(instructions.sorted_by_description()), ["a.pdf", "c.pdf", "b.pdf", "b.pdf"]);
+        assert_eq!(instructions.sorted_by_description()[3].description, "BI 3006, 2/2 (copy)");
+    }
+
+    #[test]
+    fn review_plain_text() {
+        let review = |html: bool, body: &str| Review {
+            author: "someone".to_string(),
+            date_posted: chrono::Utc::now(),
+            rating: Rating { overall: 5, parts: None, building_experience: None, playability: None, value_for_money: None },
+            title: String::new(),
+            review: body.to_string(),
+            html,
+        };
+        assert_eq!(review(true, "A <b>bold</b> build.").plain_text(), "A bold build.");
+        assert_eq!(review(true, "Fish &amp; chips<br/>&lt;3 &#233;&#x21; & more").plain_text(), "Fish & chips\n<3 é! & more");
+        assert_eq!(review(true, "unclosed <b").plain_text(), "unclosed <b");
+        assert_eq!(review(false, "A <b>bold</b> build &amp;").plain_text(), "A <b>bold</b> build &amp;");
+    }
+
+    #[test]
+    fn minifig_totals() {
+        let minifig = |owned_in_sets: usize, owned_loose: usize, owned_total: usize| MinifigCollection {
+            minifig_number: "sp001".to_string(),
+            name: "Classic Spaceman".to_string(),
+            category: "Space".to_string(),
+            owned_in_sets,
+            owned_loose,
+            owned_total,
+            wanted: false,
+        };
+        assert_eq!(minifig(3, 2, 5).computed_total(), 5);
+        assert!(minifig(3, 2, 5).is_consistent());
+        assert_eq!(minifig(3, 2, 4).computed_total(), 5);
+        assert!(!minifig(3, 2, 4).is_consistent());
+    }
+
+    #[test]
+    fn minifigs_by_category() {
+        let input = r#" {"status":"success","matches":3,"minifigs":[
+            {"minifigNumber":"sw0001a","name":"Battle Droid","category":"Star Wars / Star Wars Episode 1","ownedInSets":2,"ownedLoose":0,"ownedTotal":2,"wanted":false},
+            {"minifigNumber":"sp001","name":"Classic Spaceman","category":"Space / Classic Space","ownedInSets":1,"ownedLoose":1,"ownedTotal":2,"wanted":false},
+            {"minifigNumber":"sp002","name":"Classic Spaceman","category":"Space / Classic Space","ownedInSets":0,"ownedLoose":1,"ownedTotal":1,"wanted":true}
+        ]} "#;
+        let minifigs = serde_json::from_str::<Response<GetMinifigCollectionResponse>>(input).expect("from_str").unwrap();
+        let numbers: Vec<&str> = minifigs.filter_by_category("space / classic space").iter().map(|m| m.minifig_number.as_str()).collect();
+        assert_eq!(numbers, ["sp001", "sp002"]);
+        assert!(minifigs.filter_by_category("Castle").is_empty());
+        let numbers: Vec<&str> = minifigs.filter_by_category("Space").iter().map(|m| m.minifig_number.as_str()).collect();
+        assert_eq!(numbers, ["sp001", "sp002"]);
+        assert_eq!(minifigs.filter_by_category("star wars").len(), 1);
+        assert!(minifigs.filter_by_category("Star").is_empty());
+        assert!(minifigs.filter_by_category("Star Wars Episode 1").is_empty());
+        assert!(minifigs.filter_by_category("Space / Classic Space / Extra").is_empty());
+    }
+
+    #[test]
+    fn minifigs_owned_wanted_sorted() {
+        let input = r#" {"status":"success","matches":3,"minifigs":[
+            {"minifigNumber":"sp002","name":"Classic Spaceman","category":"Space / Classic Space","ownedInSets":0,"ownedLoose":0,"ownedTotal":0,"wanted":true},
+            {"minifigNumber":"sw0001a","name":"Battle Droid","category":"Star Wars / Star Wars Episode 1","ownedInSets":2,"ownedLoose":0,"ownedTotal":2,"wanted":false},
+            {"minifigNumber":"sp001","name":"Classic Spaceman","category":"Space / Classic Space","ownedInSets":1,"ownedLoose":1,"ownedTotal":2,"wanted":true}
+        ]} "#;
+        let minifigs = serde_json::from_str::<Response<GetMinifigCollectionResponse>>(input).expect("from_str").unwrap();
+        let numbers = |minifigs: Vec<&MinifigCollection>| minifigs.iter().map(|m| m.minifig_number.clone()).collect::<Vec<_>>();
+        assert_eq!(numbers(minifigs.owned_only()), ["sw0001a", "sp001"]);
+        assert_eq!(numbers(minifigs.wanted_only()), ["sp002", "sp001"]);
+        assert_eq!(numbers(minifigs.sorted_by_name()), ["sw0001a", "sp001", "sp002"]);
+    }
+
+    #[test]
+    fn instructions_file_extension() {
+        let instructions = |url: &str| Instructions { url: url.to_string(), description: String::new() };
+        assert_eq!(instructions("https://www.lego.com/cdn/product-assets/product.bi.core.pdf/6420986.pdf").file_extension(), Some("pdf"));
+        assert_eq!(instructions("https://example.com/files/6876.ZIP?download=1#top").file_extension(), Some("ZIP"));
+        assert_eq!(instructions("https://lego.com").file_extension(), None);
+        assert_eq!(instructions("https://lego.com/instructions/").file_extension(), None);
+        assert_eq!(instructions("https://lego.com/instructions/.pdf").file_extension(), None);
+        assert!(instructions("https://lego.com/6876.pdf").parsed_url().is_ok());
+        assert!(instructions("not a url").parsed_url().is_err());
+    }
+
+    #[test]
+    fn lego_com_france() {
+        let input = r#" {
+            "US":{"retailPrice":59.99,"dateFirstAvailable":"2021-08-01T00:00:00Z"},
+            "UK":{"retailPrice":54.99},
+            "CA":{},
+            "DE":{"retailPrice":59.99},
+            "FR":{"retailPrice":64.99,"dateLastAvailable":"2023-12-31T00:00:00Z"}
+        } "#;
+        let lego_com: LegoCom = serde_json::from_str(input).expect("from_str");
+        assert_eq!(lego_com.france.retail_price, Some(64.99));
+        assert!(lego_com.france.date_last_available.is_some());
+        assert_eq!(lego_com.get(Region::FR).retail_price, Some(64.99));
+        assert_eq!(lego_com.canada.retail_price, None);
+
+        let json = serde_json::to_value(&lego_com).expect("to_value");
+        assert_eq!(json["FR"]["retailPrice"], 64.99);
+    }
+
+    #[test]
+    fn get_additional_images() {
+        let input = r#" {"status":"success","matches":2,"additionalImages":[
+            {"thumbnailURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_1.jpg","imageURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/6876_1.jpg"},
+            {"thumbnailURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_2.jpg","imageURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/6876_2.jpg"}
+        ]} "#;
+        let mut des = Deserializer::from_str(input);
+        let resp = Response::<GetAdditionalImagesResponse>::deserialize(&mut des).expect("deserialize");
+        let images = resp.unwrap();
+        assert_eq!(images.matches, 2);
+        assert_eq!(images.additional_images.len(), 2);
+        assert_eq!(images.additional_images[1].image_url.as_deref(), Some("https://images.brickset.com/sets/AdditionalImages/6876-1/6876_2.jpg"));
+    }
 }
 
 #[cfg(test)]
@@ -116,4 +790,1647 @@ mod request_tests {
         let url = input.to_request_url().expect("try_from");
         assert_eq!(url.as_str(), "https://brickset.com/api/v3.asmx/checkKey?apiKey=12345678")
     }
+
+    /// Request building doesn't depend on the `reqwest` feature.
+    #[test]
+    fn get_sets_url() {
+        let input = GetSets::new("12345678", None, GetSetsParameters::new().theme("Space").year(1979));
+        let url = input.to_request_url().expect("to_request_url");
+        assert_eq!(url.path(), "/api/v3.asmx/getSets");
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(pairs, [
+            ("apiKey".to_string(), "12345678".to_string()),
+            ("params".to_string(), r#"{"theme":"Space","year":"1979"}"#.to_string()),
+            ("userHash".to_string(), String::new()),
+        ]);
+    }
+
+    #[test]
+    fn parse_method() {
+        let url = GetThemes::new("12345678").to_request_url().expect("to_request_url");
+        assert_eq!(super::request::parse_method(&url), Some("getThemes"));
+        let url = url::Url::parse("http://localhost:8080/api/v3.asmx/getInstructions2").unwrap();
+        assert_eq!(super::request::parse_method(&url), Some("getInstructions2"));
+        let url = url::Url::parse("https://brickset.com/api/v3.asmx/getThings").unwrap();
+        assert_eq!(super::request::parse_method(&url), None);
+        let url = url::Url::parse("https://brickset.com/api/v2.asmx/getThemes").unwrap();
+        assert_eq!(super::request::parse_method(&url), None);
+        assert_eq!(super::request::parse_method(&ENDPOINT), None);
+    }
+
+    #[test]
+    fn try_page_size() {
+        assert!(GetSetsParameters::new().try_page_size(0).is_err());
+        assert!(GetSetsParameters::new().try_page_size(1).is_ok());
+        assert!(GetSetsParameters::new().try_page_size(500).is_ok());
+        assert!(GetSetsParameters::new().try_page_size(501).is_err());
+    }
+
+    #[test]
+    fn encode_json() {
+        let input = GetSets::new("12345678", None, GetSetsParameters::new().set_id(23351));
+        let json = input.encode_json().expect("encode_json");
+        assert_eq!(json["apiKey"], "12345678");
+        assert_eq!(json["userHash"], "");
+        assert_eq!(json["params"], r#"{"setId":23351}"#);
+    }
+
+    #[test]
+    fn debug_redacts_secrets() {
+        let debug = format!("{:?}", Login::new("12345678", "alice", "hunter2"));
+        assert_eq!(debug, r#"Login { api_key: "***", username: "alice", password: "***" }"#);
+
+        let debug = format!("{:?}", GetSets::new("12345678", Some("abcdef"), GetSetsParameters::new()));
+        assert!(debug.contains(r#"user_hash: Some("***")"#), "{debug}");
+        assert!(!debug.contains("12345678") && !debug.contains("abcdef"), "{debug}");
+
+        let debug = format!("{:?}", SetCollection::new("12345678", "abcdef", 1, SetCollectionParameters::new()));
+        assert!(!debug.contains("12345678") && !debug.contains("abcdef"), "{debug}");
+    }
+
+    #[test]
+    fn to_params_json() {
+        let params = GetSetsParameters::new().theme("Space").year(1979).page_size(50);
+        let json = params.to_params_json().expect("to_params_json");
+        assert_eq!(json, r#"{"theme":"Space","year":"1979","pageSize":50}"#);
+
+        let encoded = GetSets::new("12345678", None, params).encode_json().expect("encode_json");
+        assert_eq!(encoded["params"], json);
+    }
+
+    #[test]
+    fn to_debug_json() {
+        let input = GetSets::new("12345678", None, GetSetsParameters::new().set_id(23351));
+        let json = input.to_debug_json();
+        assert_eq!(json["apiKey"], "***");
+        assert_eq!(json["userHash"], "");
+        assert_eq!(json["params"], r#"{"setId":23351}"#);
+
+        let json = Login::new("12345678", "alice", "hunter2").to_debug_json();
+        assert_eq!(json, serde_json::json!({ "apiKey": "***", "username": "alice", "password": "***" }));
+
+        let json = CheckUserHash::new("12345678", "abcdef").to_debug_json();
+        assert_eq!(json["userHash"], "***");
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn to_reqwest_json() {
+        let input = CheckKey::new("12345678");
+        let request = input.to_reqwest_json(&reqwest::Client::new()).expect("to_reqwest_json");
+        assert_eq!(request.url().as_str(), "https://brickset.com/api/v3.asmx/checkKey");
+        assert_eq!(request.headers()[reqwest::header::CONTENT_TYPE], "application/json");
+        let body = request.body().and_then(|b| b.as_bytes()).expect("body");
+        assert_eq!(body, br#"{"apiKey":"12345678"}"#);
+    }
+
+    #[cfg(all(feature = "gzip", feature = "deflate"))]
+    #[test]
+    fn accept_encoding() {
+        let request = CheckKey::new("12345678").to_reqwest(&reqwest::Client::new()).expect("to_reqwest");
+        assert_eq!(request.headers()[reqwest::header::ACCEPT_ENCODING], "gzip, deflate");
+        let request = CheckKey::new("12345678").to_reqwest_json(&reqwest::Client::new()).expect("to_reqwest_json");
+        assert_eq!(request.headers()[reqwest::header::ACCEPT_ENCODING], "gzip, deflate");
+    }
+
+    #[test]
+    fn category_and_packaging_type() {
+        let params = GetSetsParameters::new().category("Gear").packaging_type("Polybag");
+        let json = serde_json::to_string(&params).expect("to_string");
+        assert_eq!(json, r#"{"category":"Gear","packagingType":"Polybag"}"#);
+    }
+
+    #[test]
+    fn year_range() {
+        let params = GetSetsParameters::new().year_range(1978..=1981);
+        assert_eq!(params.get_years(), [1978, 1979, 1980, 1981]);
+        assert_eq!(GetSetsParameters::new().year_range(2000..=2000).get_years(), [2000]);
+        let empty = GetSetsParameters::new().year(1999).year_range(std::ops::RangeInclusive::new(2000, 1990));
+        assert!(empty.validate().is_err());
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        assert!(GetSets::new("key", None, empty.clone()).encode_query(&mut query).is_err());
+        assert!(empty.into_owned().validate().is_err());
+        assert!(GetSetsParameters::new().year_range(std::ops::RangeInclusive::new(2000, 1990)).year(1999).validate().is_ok());
+        assert!(GetSetsParameters::new().try_year_range(std::ops::RangeInclusive::new(2000, 1990)).is_err());
+        assert_eq!(GetSetsParameters::new().try_year_range(1990..=1991).unwrap().get_years(), [1990, 1991]);
+
+        assert!(GetSetsParameters::new().year_range(0..=i32::MAX).validate().is_err());
+        assert!(GetSetsParameters::new().year_range(0..=i32::MAX).get_years().is_empty());
+        assert!(GetSetsParameters::new().year_range(1900..=1950).validate().is_err());
+        assert!(GetSetsParameters::new().try_year_range(1949..=2049).is_err());
+        assert_eq!(GetSetsParameters::new().try_year_range(1949..=2048).unwrap().get_years().len(), 100);
+    }
+
+    #[test]
+    fn validate() {
+        assert!(GetSetsParameters::new().validate().is_ok());
+        assert!(GetSetsParameters::new().page_size(500).page_number(1).validate().is_ok());
+        assert!(GetSetsParameters::new().page_size(0).validate().is_err());
+        assert!(GetSetsParameters::new().page_size(501).validate().is_err());
+        assert!(GetSetsParameters::new().page_number(0).validate().is_err());
+        assert!(GetSetsParameters::new().set_id(23351).theme("Space").validate().is_ok());
+        assert!(GetSetsParameters::new().set_id(23351).query("explorer").validate().is_err());
+        assert!(GetSetsParameters::new().set_id(23351).full_set_number("10497-1").validate().is_err());
+    }
+
+    #[test]
+    fn order_by_from_str() {
+        assert!(matches!("pieces".parse::<OrderBy>(), Ok(OrderBy::Pieces)));
+        assert!(matches!("piecesDESC".parse::<OrderBy>(), Ok(OrderBy::PiecesDESC)));
+        assert!(matches!("PiecesDESC".parse::<OrderBy>(), Ok(OrderBy::PiecesDESC)));
+        assert!(matches!("Pieces DESC".parse::<OrderBy>(), Ok(OrderBy::PiecesDESC)));
+        assert!(matches!("pieces desc".parse::<OrderBy>(), Ok(OrderBy::PiecesDESC)));
+        assert!("pieces-desc".parse::<OrderBy>().is_err());
+        assert!("Pieces ASC".parse::<OrderBy>().is_err());
+        assert!("PiecesDESC DESC".parse::<OrderBy>().is_err());
+        for order_by in OrderBy::ALL {
+            assert_eq!(order_by.to_string().parse::<OrderBy>().unwrap(), *order_by);
+        }
+    }
+
+    #[test]
+    fn order_by_round_trip() {
+        for order_by in OrderBy::ALL {
+            let json = serde_json::to_string(order_by).expect("to_string");
+            assert_eq!(serde_json::from_str::<OrderBy>(&json).expect("from_str"), *order_by);
+            let api_name: String = serde_json::from_str(&json).expect("from_str");
+            assert_eq!(api_name.parse::<OrderBy>().unwrap(), *order_by);
+
+            let canonical = serde_json::to_string(order_by.as_str()).expect("to_string");
+            assert_eq!(serde_json::from_str::<OrderBy>(&canonical).expect("from_str"), *order_by);
+        }
+    }
+
+    #[test]
+    fn order_by_base() {
+        for &order_by in OrderBy::ALL {
+            let (asc, desc) = if order_by.as_str().ends_with("DESC") {
+                (order_by.reversed(), order_by)
+            } else {
+                (order_by, order_by.reversed())
+            };
+            assert!(!asc.is_descending());
+            assert!(desc.is_descending());
+            assert_eq!(asc.base(), asc);
+            assert_eq!(desc.base(), asc);
+        }
+    }
+
+    #[test]
+    fn order_by_serialize() {
+        for &order_by in OrderBy::ALL {
+            let params = GetSetsParameters::new().order_by(order_by);
+            let json = serde_json::to_string(&params).expect("to_string");
+            let expected = if order_by.is_descending() {
+                format!(r#"{{"orderBy":"{} DESC"}}"#, order_by.base())
+            } else {
+                format!(r#"{{"orderBy":"{order_by}"}}"#)
+            };
+            assert_eq!(json, expected);
+
+            let params: GetSetsParameters = serde_json::from_str(&json).expect("from_str");
+            assert_eq!(serde_json::to_string(&params).expect("to_string"), expected);
+        }
+    }
+
+    #[test]
+    fn themes() {
+        let params = GetSetsParameters::new().theme("City");
+        assert_eq!(serde_json::to_string(&params).expect("to_string"), r#"{"theme":"City"}"#);
+
+        let params = GetSetsParameters::new().themes(vec!["City", "Creator"]);
+        let json = serde_json::to_string(&params).expect("to_string");
+        assert_eq!(json, r#"{"theme":"City, Creator"}"#);
+
+        let params: GetSetsParameters = serde_json::from_str(&json).expect("from_str");
+        assert_eq!(serde_json::to_string(&params).expect("to_string"), json);
+    }
+
+    #[test]
+    fn updated_since_datetime() {
+        let datetime = chrono::DateTime::parse_from_rfc3339("2023-06-15T23:59:59Z").unwrap().with_timezone(&chrono::Utc);
+        let params = GetSetsParameters::new().updated_since_datetime(datetime);
+        assert_eq!(serde_json::to_string(&params).expect("to_string"), r#"{"updatedSince":"2023-06-15"}"#);
+    }
+
+    #[test]
+    fn requires_user_hash() {
+        assert!(!GetSetsParameters::new().query("fire truck").requires_user_hash());
+        assert!(GetSetsParameters::new().owned_by_user(true).requires_user_hash());
+        assert!(GetSetsParameters::new().wanted_by_user(true).requires_user_hash());
+        assert!(!GetSetsParameters::new().wanted_by_user(true).wanted_by_user(false).requires_user_hash());
+    }
+
+    #[test]
+    fn get_sets_parameters_accessors() {
+        let params = GetSetsParameters::new()
+            .theme("City")
+            .wanted_by_user(true)
+            .order_by(OrderBy::PiecesDESC)
+            .page_size(100);
+        assert_eq!(params.get_themes(), &["City"]);
+        assert!(params.get_wanted_by_user());
+        assert!(!params.get_owned_by_user());
+        assert_eq!(params.get_order_by(), Some(OrderBy::PiecesDESC));
+        assert_eq!(params.get_page_size(), Some(100));
+        assert_eq!(params.get_page_number(), None);
+    }
+
+    #[test]
+    fn set_collection_parameters_clear() {
+        let json = |params: SetCollectionParameters| serde_json::to_string(&params).expect("to_string");
+        assert_eq!(json(SetCollectionParameters::new()), "{}");
+        assert_eq!(json(SetCollectionParameters::new().rating(4)), r#"{"rating":4}"#);
+        assert_eq!(json(SetCollectionParameters::new().clear_rating()), r#"{"rating":0}"#);
+        assert_eq!(json(SetCollectionParameters::new().notes("Missing a piece")), r#"{"notes":"Missing a piece"}"#);
+        assert_eq!(json(SetCollectionParameters::new().clear_notes()), r#"{"notes":""}"#);
+    }
+
+    #[test]
+    fn get_sets_parameters_presets() {
+        let latest = GetSetsParameters::latest();
+        assert_eq!(latest.get_order_by(), Some(OrderBy::YearFromDESC));
+        assert_eq!(latest.get_page_size(), Some(20));
+
+        let theme = GetSetsParameters::for_theme("Space");
+        assert_eq!(theme.get_themes(), &["Space"]);
+        assert_eq!(theme.get_order_by(), Some(OrderBy::Number));
+        assert_eq!(theme.get_page_size(), None);
+    }
+
+    #[test]
+    fn get_sets_parameters_owned() {
+        fn build(query: &str, themes: &[&str]) -> GetSetsParameters<'static> {
+            GetSetsParameters::new()
+                .query(query.to_string())
+                .themes(themes.iter().map(|theme| theme.to_string()))
+                .tag(format!("{query} tag"))
+        }
+
+        let params = build("fire", &["City", "Town"]);
+        assert_eq!(params.get_query(), Some("fire"));
+        assert_eq!(params.get_themes(), &["City", "Town"]);
+        assert_eq!(params.get_tag(), Some("fire tag"));
+
+        let subtheme = String::from("Fire");
+        let params = GetSetsParameters::new().subtheme(&subtheme).into_owned();
+        drop(subtheme);
+        assert_eq!(serde_json::to_string(&params).expect("to_string"), r#"{"subtheme":"Fire"}"#);
+
+        let json = r#"{"query":"fire","theme":"City, Town"}"#;
+        let params: GetSetsParameters = serde_json::from_str(json).expect("from_str");
+        assert!(matches!(params.get_themes()[1], std::borrow::Cow::Borrowed("Town")));
+        assert_eq!(params.get_query(), Some("fire"));
+    }
+
+    #[test]
+    fn set_number() {
+        let number: SetNumber = "6876-1".parse().expect("parse");
+        assert_eq!(number, SetNumber::new("6876", Some(1)));
+        assert_eq!(number.to_string(), "6876-1");
+        assert_eq!(number.full(), "6876-1");
+
+        let number: SetNumber = " 6876 ".parse().expect("parse");
+        assert_eq!(number, SetNumber::new("6876", None));
+        assert_eq!(number.to_string(), "6876");
+        assert_eq!(number.full(), "6876-1");
+
+        let number: SetNumber = "10497 - 2".parse().expect("parse");
+        assert_eq!(number.to_string(), "10497-2");
+
+        assert!("abc-".parse::<SetNumber>().is_err());
+        assert!("-1".parse::<SetNumber>().is_err());
+        assert!("6876-x".parse::<SetNumber>().is_err());
+        assert!("".parse::<SetNumber>().is_err());
+    }
+}
+
+#[cfg(test)]
+mod util_tests {
+    use chrono::NaiveDate;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct UpdatedSince {
+        #[serde(with = "super::util::updated_since_format")]
+        updated_since: Option<NaiveDate>,
+    }
+
+    #[test]
+    fn flag_accepted() {
+        for json in ["1", r#""1""#, "true"] {
+            assert!(serde_json::from_str::<super::util::Flag>(json).is_ok(), "{json}");
+        }
+    }
+
+    #[test]
+    fn flag_rejected() {
+        for json in ["0", "2", "-1", "1.0", r#""0""#, r#""true""#, r#""""#, "false", "null", "[]", "{}"] {
+            assert!(serde_json::from_str::<super::util::Flag>(json).is_err(), "{json}");
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Price {
+        #[serde(default)]
+        #[serde(with = "super::util::lenient_f64")]
+        retail_price: Option<f64>,
+    }
+
+    #[derive(Deserialize)]
+    struct Rating {
+        #[serde(default)]
+        #[serde(deserialize_with = "super::util::lenient_f64::deserialize_zero_none")]
+        rating: Option<f64>,
+    }
+
+    #[test]
+    fn lenient_f64() {
+        for json in [r#"{"retail_price":59.99}"#, r#"{"retail_price":"59.99"}"#, r#"{"retail_price":" 59.99 "}"#] {
+            let price: Price = serde_json::from_str(json).expect(json);
+            assert_eq!(price.retail_price, Some(59.99), "{json}");
+        }
+        for json in [r#"{"retail_price":60}"#, r#"{"retail_price":"60"}"#] {
+            let price: Price = serde_json::from_str(json).expect(json);
+            assert_eq!(price.retail_price, Some(60.0), "{json}");
+        }
+        for json in ["{}", r#"{"retail_price":null}"#, r#"{"retail_price":""}"#] {
+            let price: Price = serde_json::from_str(json).expect(json);
+            assert_eq!(price.retail_price, None, "{json}");
+        }
+        for json in [r#"{"retail_price":"abc"}"#, r#"{"retail_price":true}"#] {
+            assert!(serde_json::from_str::<Price>(json).is_err(), "{json}");
+        }
+        for value in ["NaN", "nan", "inf", "-inf", "infinity", "1e999"] {
+            let json = format!(r#"{{"retail_price":"{value}"}}"#);
+            assert!(serde_json::from_str::<Price>(&json).is_err(), "{json}");
+            let json = format!(r#"{{"rating":"{value}"}}"#);
+            assert!(serde_json::from_str::<Rating>(&json).is_err(), "{json}");
+        }
+        let rating: Rating = serde_json::from_str(r#"{"rating":"0"}"#).expect("from_str");
+        assert_eq!(rating.rating, None);
+    }
+
+    #[test]
+    fn updated_since_none() {
+        let json = serde_json::to_string(&UpdatedSince { updated_since: None }).expect("to_string");
+        assert_eq!(json, r#"{"updated_since":null}"#);
+        let value: UpdatedSince = serde_json::from_str(&json).expect("from_str");
+        assert!(value.updated_since.is_none());
+
+        let params = super::request::GetSetsParameters::new();
+        assert_eq!(serde_json::to_string(&params).expect("to_string"), "{}");
+    }
+
+    #[test]
+    fn updated_since_some() {
+        let date = NaiveDate::from_ymd_opt(2023, 6, 15);
+        let json = serde_json::to_string(&UpdatedSince { updated_since: date }).expect("to_string");
+        assert_eq!(json, r#"{"updated_since":"2023-06-15"}"#);
+        let value: UpdatedSince = serde_json::from_str(&json).expect("from_str");
+        assert_eq!(value.updated_since, date);
+    }
+}
+
+#[cfg(all(test, feature = "reqwest"))]
+mod reqwest_api_tests {
+    use super::reqwest_api::{ClientWrapper, DEFAULT_USER_AGENT, Download, Error, RequestData, ResponseData, RetryPolicy, Transport};
+    use super::request::{GetSetsParameters, SetCollectionParameters};
+    use std::{sync::Mutex, time::Duration};
+    use url::Url;
+    use wiremock::{matchers::{body_string_contains, method, path}, Mock, MockServer, ResponseTemplate};
+
+    fn endpoint(server: &MockServer) -> Url {
+        Url::parse(&format!("{}/api/v3.asmx/", server.uri())).expect("endpoint")
+    }
+
+    #[test]
+    fn prepare() {
+        let endpoint = Url::parse("http://localhost:8080/api/v3.asmx/").expect("endpoint");
+        let client = reqwest::Client::new();
+        let wrapper = ClientWrapper::builder("12345678", &client)
+            .endpoint(endpoint)
+            .user_hash("abcdef")
+            .timeout(Duration::from_secs(5))
+            .daily_limit(10, true)
+            .build()
+            .expect("build");
+
+        let request = wrapper.prepare(super::request::GetUserNotes::new("", "")).expect("prepare");
+        assert_eq!(request.method(), reqwest::Method::POST);
+        assert_eq!(request.url().as_str(), "http://localhost:8080/api/v3.asmx/getUserNotes");
+        assert_eq!(request.timeout(), Some(&Duration::from_secs(5)));
+        let body = request.body().and_then(|b| b.as_bytes()).expect("body");
+        assert_eq!(body, b"apiKey=12345678&userHash=abcdef");
+        assert_eq!(wrapper.remaining_quota(), Some(10));
+
+        let request = wrapper.prepare(super::request::GetUserNotes::new("87654321", "ghijkl")).expect("prepare");
+        let body = request.body().and_then(|b| b.as_bytes()).expect("body");
+        assert_eq!(body, b"apiKey=87654321&userHash=ghijkl");
+
+        let params = GetSetsParameters::new().owned_by_user(true);
+        let request = wrapper.prepare(super::request::GetSets::new("", None, params)).expect("prepare");
+        let body = String::from_utf8_lossy(request.body().and_then(|b| b.as_bytes()).expect("body")).into_owned();
+        assert!(body.starts_with("apiKey=12345678&"), "{body}");
+        assert!(body.ends_with("&userHash=abcdef"), "{body}");
+    }
+
+    #[test]
+    fn prepare_matches_to_reqwest() {
+        use super::request::BricksetRequest;
+
+        let client = reqwest::Client::new();
+        let wrapper = ClientWrapper::builder("12345678", &client).client_user_agent().build().expect("build");
+        let request = super::request::GetThemes::new("12345678");
+        let prepared = wrapper.prepare(super::request::GetThemes::new("12345678")).expect("prepare");
+        let built = request.to_reqwest(&client).expect("to_reqwest");
+        assert_eq!(prepared.url(), built.url());
+        assert_eq!(prepared.headers(), built.headers());
+        assert_eq!(prepared.body().and_then(|b| b.as_bytes()), built.body().and_then(|b| b.as_bytes()));
+    }
+
+    #[test]
+    fn user_agent() {
+        let client = reqwest::Client::new();
+        let user_agent = |wrapper: &ClientWrapper| {
+            let request = wrapper.prepare(super::request::CheckKey::new("12345678")).expect("prepare");
+            request.headers().get(reqwest::header::USER_AGENT).map(|ua| ua.to_str().unwrap().to_string())
+        };
+
+        let mut wrapper = ClientWrapper::new("12345678", &client);
+        assert!(DEFAULT_USER_AGENT.starts_with("brickset-rs/"));
+        assert_eq!(user_agent(&wrapper).as_deref(), Some(DEFAULT_USER_AGENT));
+        wrapper.set_user_agent("my-app/1.0").expect("set_user_agent");
+        assert_eq!(user_agent(&wrapper).as_deref(), Some("my-app/1.0"));
+        wrapper.clear_user_agent();
+        assert_eq!(user_agent(&wrapper), None);
+        assert!(wrapper.set_user_agent("bad\nagent").is_err());
+
+        let wrapper = ClientWrapper::builder("12345678", &client).build().expect("build");
+        assert_eq!(user_agent(&wrapper).as_deref(), Some(DEFAULT_USER_AGENT));
+        let wrapper = ClientWrapper::builder("12345678", &client).client_user_agent().build().expect("build");
+        assert_eq!(user_agent(&wrapper), None);
+
+        let wrapper = ClientWrapper::builder("12345678", &client)
+            .user_agent("my-app/1.0")
+            .build()
+            .expect("build");
+        assert_eq!(user_agent(&wrapper).as_deref(), Some("my-app/1.0"));
+        assert!(ClientWrapper::builder("12345678", &client).user_agent("bad\nagent").build().is_err());
+    }
+
+    #[tokio::test]
+    async fn client_user_agent() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkKey"))
+            .and(wiremock::matchers::header("User-Agent", DEFAULT_USER_AGENT))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkKey"))
+            .and(wiremock::matchers::header("User-Agent", "my-app/1.0"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::builder().user_agent("my-app/1.0").build().expect("build");
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.check_key().await.expect("check_key");
+        client.clear_user_agent();
+        client.check_key().await.expect("check_key");
+    }
+
+    #[tokio::test]
+    async fn check_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkKey"))
+            .and(body_string_contains("apiKey=12345678"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.check_key().await.expect("check_key");
+    }
+
+    #[test]
+    fn error_predicates() {
+        let http = |status: u16| Error::Http { status: reqwest::StatusCode::from_u16(status).unwrap(), body: String::new() };
+        let response = |message: &str| Error::Response(super::response::Error { message: message.to_string() });
+
+        assert!(http(503).is_retryable());
+        assert!(!http(404).is_retryable());
+        assert!(Error::Timeout.is_retryable());
+        assert!(!Error::NotLoggedIn.is_retryable());
+        assert!(!response("Invalid user hash").is_retryable());
+        let decode = serde_json::from_str::<u32>("x").unwrap_err();
+        assert!(!Error::Decode { method: "getSets", source: decode, body: "x".to_string() }.is_retryable());
+
+        assert!(response("Invalid user hash").is_auth_error());
+        assert!(response("Invalid userHash").is_auth_error());
+        assert!(!response("Invalid API key").is_auth_error());
+        assert!(!http(401).is_auth_error());
+    }
+
+    #[tokio::test]
+    async fn builder() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=abcdef"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"userNotes":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let wrapper = ClientWrapper::builder("12345678", &client)
+            .endpoint(endpoint(&server))
+            .user_hash("abcdef")
+            .retry_policy(RetryPolicy::default())
+            .daily_limit(100, true)
+            .timeout(Duration::from_secs(5))
+            .build()
+            .expect("build");
+        assert_eq!(wrapper.remaining_quota(), Some(100));
+        wrapper.get_notes().await.expect("get_notes");
+
+        let no_slash = Url::parse("http://localhost:8080/api/v3.asmx").expect("url");
+        assert!(ClientWrapper::builder("12345678", &client).endpoint(no_slash).build().is_err());
+        assert!(ClientWrapper::builder("12345678", &client).timeout(Duration::ZERO).build().is_err());
+        assert!(ClientWrapper::builder("12345678", &client).daily_limit(0, true).build().is_err());
+        assert!(ClientWrapper::builder("12345678", &client).daily_limit(0, false).build().is_ok());
+        assert!(ClientWrapper::builder("12345678", &client).auto_relogin("alice", "").build().is_err());
+    }
+
+    #[tokio::test]
+    async fn auto_relogin() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"error","message":"Invalid user hash"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"userNotes":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/login"))
+            .and(body_string_contains("username=alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","hash":"fresh"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.force_reuse_login("stale");
+
+        let err = client.get_notes().await.expect_err("get_notes without auto re-login");
+        assert!(err.is_auth_error());
+        assert_eq!(client.user_hash().as_deref(), Some("stale"));
+
+        client.set_auto_relogin("alice", "hunter2");
+        let stale = client.user_hash().expect("user_hash");
+        let notes = client.get_notes().await.expect("get_notes with auto re-login");
+        assert_eq!(notes.matches, 0);
+        assert_eq!(&*stale, "stale");
+        assert_eq!(client.user_hash().as_deref(), Some("fresh"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_relogin() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"error","message":"Invalid user hash"}"#))
+            .expect(4)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"userNotes":[]}"#))
+            .expect(4)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/login"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","hash":"fresh"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::builder("12345678", &client)
+            .endpoint(endpoint(&server))
+            .user_hash("stale")
+            .auto_relogin("alice", "hunter2")
+            .build()
+            .expect("build");
+        let results = futures::future::join_all((0..4).map(|_| client.get_notes())).await;
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+        assert_eq!(client.user_hash().as_deref(), Some("fresh"));
+    }
+
+    #[tokio::test]
+    async fn log_in_or_reuse() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkUserHash"))
+            .and(body_string_contains("userHash=good"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkUserHash"))
+            .and(body_string_contains("userHash=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"error","message":"Invalid user hash"}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/login"))
+            .and(body_string_contains("username=alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","hash":"fresh"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+
+        let login = client.log_in_or_reuse(Some("good"), "alice", "hunter2").await.expect("reuse");
+        assert_eq!(login.hash, "good");
+        assert_eq!(client.user_hash().as_deref(), Some("good"));
+
+        let login = client.log_in_or_reuse(Some("stale"), "alice", "hunter2").await.expect("fallback");
+        assert_eq!(login.hash, "fresh");
+        assert_eq!(client.user_hash().as_deref(), Some("fresh"));
+
+        let login = client.log_in_or_reuse(None, "alice", "hunter2").await.expect("log_in");
+        assert_eq!(login.hash, "fresh");
+    }
+
+    #[tokio::test]
+    async fn get_set() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setId%22%3A31278%2C%22extendedData%22%3A1"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(r#"{{"status":"success","matches":1,"sets":[{}]}}"#, super::response_tests::SET)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setId%22%3A1%7D"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"sets":[]}"#))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let set = client.get_set(31278, true).await.expect("get_set").expect("set");
+        assert_eq!(set.set_id, 31278);
+        assert!(client.get_set(1, false).await.expect("get_set").is_none());
+    }
+
+    #[tokio::test]
+    async fn resolve_set_id() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setNumber%22%3A%2210497-1%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(r#"{{"status":"success","matches":1,"sets":[{}]}}"#, super::response_tests::SET)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setNumber%22%3A%2299999-1%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"sets":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        assert_eq!(client.resolve_set_id("10497-1").await.expect("resolve_set_id"), Some(31278));
+        assert_eq!(client.resolve_set_id("99999-1").await.expect("resolve_set_id"), None);
+    }
+
+    #[tokio::test]
+    async fn get_set_by_number() {
+        let set = |set_id: u64, number: &str, variant: usize| super::response_tests::SET
+            .replace("31278", &set_id.to_string())
+            .replace(r#""number": "10497""#, &format!(r#""number": "{number}""#))
+            .replace(r#""numberVariant": 1"#, &format!(r#""numberVariant": {variant}"#));
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("query%22%3A%2210497%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"status":"success","matches":3,"sets":[{},{},{}]}}"#,
+                set(1, "10497a", 1), set(2, "10497", 2), set(3, "10497", 1),
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setNumber%22%3A%2210497-2%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"status":"success","matches":2,"sets":[{},{}]}}"#,
+                set(3, "10497", 1), set(2, "10497", 2),
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setNumber%22%3A%2210497-3%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"status":"success","matches":1,"sets":[{}]}}"#,
+                set(3, "10497", 1),
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("query%22%3A%22fire%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"status":"success","matches":1,"sets":[{}]}}"#,
+                set(4, "60107", 1),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let get = |number: &'static str| {
+            let client = &client;
+            async move { client.get_set_by_number(number, false).await.expect("get_set_by_number").map(|set| set.set_id) }
+        };
+        assert_eq!(get("10497").await, Some(3));
+        assert_eq!(get("10497-2").await, Some(2));
+        assert_eq!(get(" 10497 - 2 ").await, Some(2));
+        assert_eq!(get("10497-3").await, None);
+        assert_eq!(get("fire").await, Some(4));
+    }
+
+    #[tokio::test]
+    async fn get_reviews_limited() {
+        let review = |author: &str| format!(r#"{{"author":"{author}","datePosted":"2023-01-01T00:00:00Z","rating":{{"overall":5,"parts":0,"buildingExperience":0,"playability":0,"valueForMoney":0}},"title":"","review":"","HTML":false}}"#);
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getReviews"))
+            .and(body_string_contains("setID=26725"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"status":"success","matches":3,"reviews":[{},{},{}]}}"#,
+                review("a"), review("b"), review("c"),
+            )))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let reviews = client.get_reviews_limited(26725, 2).await.expect("get_reviews_limited");
+        assert_eq!(reviews.matches, 3);
+        let authors: Vec<&str> = reviews.iter().map(|review| review.author.as_str()).collect();
+        assert_eq!(authors, ["a", "b"]);
+        assert_eq!(client.get_reviews_limited(26725, 10).await.expect("get_reviews_limited").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn download_instructions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/instructions/6876.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"%PDF-1.4".to_vec()))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.set_daily_limit(1, true);
+
+        let instructions = |file: &str| super::response::Instructions {
+            url: format!("{}/instructions/{file}", server.uri()),
+            description: String::new(),
+        };
+        let pdf = client.download_instructions(&instructions("6876.pdf")).await.expect("download_instructions");
+        assert_eq!(&pdf[..], b"%PDF-1.4");
+        assert_eq!(client.remaining_quota(), Some(1));
+
+        match client.download_instructions(&instructions("missing.pdf")).await {
+            Err(Error::Http { status, .. }) => assert_eq!(status, 404),
+            other => panic!("expected Error::Http, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn download_instructions_conditional() {
+        const LAST_MODIFIED: &str = "Sat, 01 Jan 2022 00:00:00 GMT";
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/instructions/6876.pdf"))
+            .and(wiremock::matchers::header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/instructions/6876.pdf"))
+            // `matchers::header` splits values on commas, which dates contain
+            .and(|request: &wiremock::Request| request.headers.get("If-Modified-Since").is_some_and(|date| date == LAST_MODIFIED))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/instructions/6876.pdf"))
+            .respond_with(ResponseTemplate::new(200)
+                .insert_header("ETag", "\"v2\"")
+                .insert_header("Last-Modified", LAST_MODIFIED)
+                .set_body_bytes(b"%PDF-1.4".to_vec()))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let url = format!("{}/instructions/6876.pdf", server.uri());
+
+        match client.download_instructions_conditional(&url, None, None).await.expect("download") {
+            Download::Modified { bytes, etag, last_modified } => {
+                assert_eq!(&bytes[..], b"%PDF-1.4");
+                assert_eq!(etag.as_deref(), Some("\"v2\""));
+                assert_eq!(last_modified.as_deref(), Some(LAST_MODIFIED));
+            }
+            Download::NotModified => panic!("expected Download::Modified"),
+        }
+        assert!(matches!(client.download_instructions_conditional(&url, Some("\"v1\""), None).await, Ok(Download::NotModified)));
+        assert!(matches!(client.download_instructions_conditional(&url, Some("\"v0\""), None).await, Ok(Download::Modified { .. })));
+        assert!(matches!(client.download_instructions_conditional(&url, None, Some(LAST_MODIFIED)).await, Ok(Download::NotModified)));
+        assert!(matches!(client.download_instructions_conditional(&url, None, Some("Fri, 01 Jan 2021 00:00:00 GMT")).await, Ok(Download::Modified { .. })));
+    }
+
+    #[tokio::test]
+    async fn get_additional_images() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getAdditionalImages"))
+            .and(body_string_contains("setID=26725"))
+            .and(body_string_contains("apiKey=12345678"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":2,"additionalImages":[
+                {"thumbnailURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_1.jpg","imageURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/6876_1.jpg"},
+                {"thumbnailURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_2.jpg","imageURL":"https://images.brickset.com/sets/AdditionalImages/6876-1/6876_2.jpg"}
+            ]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        assert!(!client.is_logged_in());
+        let images = client.get_additional_images(26725).await.expect("get_additional_images");
+        assert_eq!(images.matches, 2);
+        let urls: Vec<_> = images.additional_images.iter()
+            .map(|image| (image.thumbnail_url.as_deref().unwrap(), image.image_url.as_deref().unwrap()))
+            .collect();
+        assert_eq!(urls, [
+            ("https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_1.jpg", "https://images.brickset.com/sets/AdditionalImages/6876-1/6876_1.jpg"),
+            ("https://images.brickset.com/sets/AdditionalImages/6876-1/tn_6876_2.jpg", "https://images.brickset.com/sets/AdditionalImages/6876-1/6876_2.jpg"),
+        ]);
+    }
+
+    #[cfg(feature = "record")]
+    #[tokio::test]
+    async fn record_and_play_back() {
+        use super::reqwest_api::record::{PlaybackTransport, RecordingTransport};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":1,"themes":[{"theme":"Icons","setCount":100,"subthemeCount":5,"yearFrom":2000,"yearTo":2024}]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = std::env::temp_dir().join(format!("brickset-record-{}", std::process::id()));
+        let recorder = RecordingTransport::new(reqwest::Client::new(), &dir).expect("new");
+        let client = ClientWrapper::with_endpoint("12345678", &recorder, endpoint(&server));
+        client.get_themes().await.expect("get_themes");
+        assert!(dir.join("getThemes.json").is_file());
+
+        let playback = PlaybackTransport::new(&dir);
+        let client = ClientWrapper::new("12345678", &playback);
+        let themes = client.get_themes().await.expect("get_themes");
+        assert_eq!(themes.themes[0].name, "Icons");
+        assert!(matches!(client.get_years("Icons").await, Err(Error::Io(_))));
+
+        std::fs::remove_dir_all(&dir).expect("remove_dir_all");
+    }
+
+    #[tokio::test]
+    async fn retry() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .with_priority(1)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"themes":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.set_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+        client.set_daily_limit(10, false);
+        let themes = client.get_themes().await.expect("get_themes");
+        assert_eq!(themes.matches, 0);
+        // The two failed attempts count against the limit too
+        assert_eq!(client.remaining_quota(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn retry_quota_exceeded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.set_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+        client.set_daily_limit(2, true);
+        assert!(matches!(client.get_themes().await, Err(Error::QuotaExceeded)));
+        assert_eq!(client.remaining_quota(), Some(0));
+    }
+
+    #[tokio::test]
+    async fn no_retry_set_collection() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/setCollection"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.set_retry_policy(RetryPolicy::new(3, Duration::from_millis(1)));
+        let params = SetCollectionParameters::new().wanted(true);
+        let result = client.set_collection_as("abcdef", 26725, params).await;
+        match result {
+            Err(Error::Http { status, body }) => {
+                assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+                assert_eq!(body, "Service Unavailable");
+            }
+            _ => panic!("expected Error::Http"),
+        }
+    }
+
+    #[tokio::test]
+    async fn daily_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/checkKey"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        assert_eq!(client.remaining_quota(), None);
+        client.set_daily_limit(2, true);
+        client.check_key().await.expect("check_key");
+        assert_eq!(client.remaining_quota(), Some(1));
+        client.check_key().await.expect("check_key");
+        assert_eq!(client.remaining_quota(), Some(0));
+        assert!(matches!(client.check_key().await, Err(Error::QuotaExceeded)));
+    }
+
+    #[tokio::test]
+    async fn as_methods() {
+        let server = MockServer::start().await;
+        let responses = [
+            ("getSets", r#"{"status":"success","matches":0,"sets":[]}"#),
+            ("setCollection", r#"{"status":"success"}"#),
+            ("getUserNotes", r#"{"status":"success","matches":0,"userNotes":[]}"#),
+            ("getMinifigCollection", r#"{"status":"success","matches":0,"minifigs":[]}"#),
+            ("setMinifigCollection", r#"{"status":"success"}"#),
+            ("getUserMinifigNotes", r#"{"status":"success","matches":0,"userMinifigNotes":[]}"#),
+        ];
+        for (method_name, body) in responses {
+            Mock::given(method("POST"))
+                .and(path(format!("/api/v3.asmx/{method_name}")))
+                .and(body_string_contains("userHash=other"))
+                .respond_with(ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        assert!(!client.is_logged_in());
+        client.get_sets_as("other", GetSetsParameters::new().owned_by_user(true)).await.expect("get_sets_as");
+        client.set_collection_as("other", 1, SetCollectionParameters::new().wanted(true)).await.expect("set_collection_as");
+        client.get_notes_as("other").await.expect("get_notes_as");
+        client.get_minifig_collection_as("other", super::request::GetMinifigCollectionParameters::owned()).await.expect("get_minifig_collection_as");
+        client.set_minifig_collection_as("other", "sw0001", super::request::SetMinifigCollectionParameters::new().wanted(true)).await.expect("set_minifig_collection_as");
+        client.get_minifig_notes_as("other").await.expect("get_minifig_notes_as");
+        let results = client.set_collection_many_as("other", [(1, SetCollectionParameters::new().owned(1))], 1).await;
+        assert!(results[0].is_ok());
+
+        // get_sets uses the saved token, through get_sets_as
+        let mut client = client;
+        client.force_reuse_login("other");
+        client.get_sets(GetSetsParameters::new().owned_by_user(true)).await.expect("get_sets");
+    }
+
+    #[tokio::test]
+    async fn get_sets_not_logged_in() {
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::new("12345678", &client);
+        let params = GetSetsParameters::new().owned_by_user(true);
+        assert!(matches!(client.get_sets(params).await, Err(Error::NotLoggedIn)));
+    }
+
+    #[tokio::test]
+    async fn decode_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":"many"}"#))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let err = client.get_themes().await.unwrap_err();
+        assert!(matches!(&err, Error::Decode { method: "getThemes", body, .. } if body == r#"{"status":"success","matches":"many"}"#));
+        assert!(err.to_string().starts_with("failed to decode getThemes response: "));
+    }
+
+    #[tokio::test]
+    async fn decode_error_truncated_body() {
+        let server = MockServer::start().await;
+        let body = format!("<html>{}</html>", "é".repeat(1000));
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(body.clone()))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        match client.get_themes().await {
+            Err(Error::Decode { body: truncated, .. }) => {
+                assert!(truncated.len() <= 500);
+                assert!(truncated.len() >= 498);
+                assert!(body.starts_with(&truncated));
+            }
+            _ => panic!("expected Error::Decode"),
+        }
+    }
+
+    #[tokio::test]
+    async fn decode_error_invalid_utf8() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"{\"status\":\xff}".to_vec()))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        match client.get_themes().await {
+            Err(Error::Decode { body, .. }) => assert_eq!(body, "{\"status\":\u{fffd}}"),
+            _ => panic!("expected Error::Decode"),
+        }
+    }
+
+    /// Returns the same body for every request, and records the bodies of requests sent.
+    struct FakeTransport {
+        body: &'static str,
+        sent: Mutex<Vec<String>>,
+    }
+
+    impl Transport for FakeTransport {
+        async fn execute(&self, request: RequestData) -> Result<ResponseData, Error> {
+            self.sent.lock().unwrap().push(String::from_utf8_lossy(&request.body).into_owned());
+            Ok(ResponseData { status: reqwest::StatusCode::OK, headers: Default::default(), body: self.body.into() })
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    type RecordedSpan = (&'static tracing::Metadata<'static>, Vec<(&'static str, String)>);
+
+    /// Records the metadata and fields of every span.
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct SpanRecorder {
+        spans: Mutex<Vec<RecordedSpan>>,
+        entered: Mutex<Vec<tracing::span::Id>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct FieldRecorder<'a>(&'a mut Vec<(&'static str, String)>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for FieldRecorder<'_> {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name(), format!("{value:?}")));
+        }
+
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.push((field.name(), value.to_string()));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut spans = self.spans.lock().unwrap();
+            let mut fields = Vec::new();
+            span.record(&mut FieldRecorder(&mut fields));
+            spans.push((span.metadata(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+            values.record(&mut FieldRecorder(fields));
+        }
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, _: &tracing::Event<'_>) {}
+
+        fn enter(&self, span: &tracing::span::Id) {
+            self.entered.lock().unwrap().push(span.clone());
+        }
+
+        fn exit(&self, _: &tracing::span::Id) {
+            self.entered.lock().unwrap().pop();
+        }
+
+        fn current_span(&self) -> tracing_core::span::Current {
+            match self.entered.lock().unwrap().last() {
+                Some(id) => {
+                    let metadata = self.spans.lock().unwrap()[id.into_u64() as usize - 1].0;
+                    tracing_core::span::Current::new(id.clone(), metadata)
+                }
+                None => tracing_core::span::Current::none(),
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn tracing_span() {
+        const BODY: &str = r#"{"status":"success","matches":0,"themes":[]}"#;
+
+        let recorder = std::sync::Arc::new(SpanRecorder::default());
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let transport = FakeTransport { body: BODY, sent: Mutex::default() };
+        let client = ClientWrapper::new("12345678", &transport);
+        client.get_themes().await.expect("get_themes");
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans.len(), 1);
+        let (metadata, fields) = &spans[0];
+        assert_eq!(metadata.name(), "brickset_request");
+        assert_eq!(fields.as_slice(), [
+            ("method", "getThemes".to_string()),
+            ("status", "200".to_string()),
+            ("response_size", BODY.len().to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn fake_transport() {
+        let transport = FakeTransport {
+            body: r#"{"status":"success","matches":1,"years":[{"theme":"Space","year":1979,"setCount":5}]}"#,
+            sent: Mutex::default(),
+        };
+        let client = ClientWrapper::new("12345678", &transport);
+        let years = client.get_years("Space").await.expect("get_years");
+        assert_eq!(years.years[0].set_count, 5);
+        assert_eq!(transport.sent.lock().unwrap().as_slice(), ["apiKey=12345678&theme=Space"]);
+    }
+
+    #[tokio::test]
+    async fn bare_empty_array() {
+        let transport = FakeTransport { body: " [ ] ", sent: Mutex::default() };
+        let client = ClientWrapper::new("12345678", &transport);
+        let sets = client.get_sets(GetSetsParameters::new().query("no such set")).await.expect("get_sets");
+        assert_eq!(sets.matches, 0);
+        assert!(sets.sets.is_empty());
+
+        let transport = FakeTransport { body: "[1]", sent: Mutex::default() };
+        let client = ClientWrapper::new("12345678", &transport);
+        let err = client.get_sets(GetSetsParameters::new()).await.unwrap_err();
+        assert!(matches!(err, Error::Decode { method: "getSets", .. }));
+
+        let transport = FakeTransport { body: "[]", sent: Mutex::default() };
+        let client = ClientWrapper::new("12345678", &transport);
+        let years = client.get_years("Space").await.expect("get_years");
+        assert!(years.years.is_empty());
+        let err = client.check_user_hash("abcdef").await.unwrap_err();
+        assert!(matches!(err, Error::Decode { method: "checkUserHash", .. }));
+
+        let transport = FakeTransport { body: r#"{"status":"success","matches":0}"#, sent: Mutex::default() };
+        let client = ClientWrapper::new("12345678", &transport);
+        let err = client.get_sets(GetSetsParameters::new()).await.unwrap_err();
+        assert!(matches!(err, Error::Decode { method: "getSets", .. }));
+    }
+
+    #[tokio::test]
+    async fn get_theme_tree() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":1,"themes":[
+                {"theme":"Space","setCount":812,"subthemeCount":31,"yearFrom":1978,"yearTo":2023}
+            ]}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSubthemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":1,"subthemes":[
+                {"theme":"Space","subtheme":"Blacktron","setCount":12,"yearFrom":1987,"yearTo":1988}
+            ]}"#))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getYears"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":1,"years":[
+                {"theme":"Space","year":1987,"setCount":14}
+            ]}"#))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let tree = client.get_theme_tree("space").await.expect("get_theme_tree");
+        assert_eq!(tree.theme.name, "Space");
+        assert_eq!(tree.subthemes[0].name, "Blacktron");
+        assert_eq!(tree.years[0].year, 1987);
+        assert!(matches!(client.get_theme_tree("castle").await, Err(Error::Request(_))));
+    }
+
+    /// Mount a `getSets` page containing sets with IDs `ids`, out of `matches` in total.
+    async fn mount_sets_page(server: &MockServer, page: usize, matches: usize, ids: &[u64]) {
+        let sets: Vec<serde_json::Value> = ids.iter()
+            .map(|id| {
+                let mut set: serde_json::Value = serde_json::from_str(super::response_tests::SET).expect("from_str");
+                set["setID"] = (*id).into();
+                set
+            })
+            .collect();
+        let sets = serde_json::to_string(&sets).expect("to_string");
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains(format!("pageNumber%22%3A{page}%7D")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(r#"{{"status":"success","matches":{matches},"sets":{sets}}}"#)))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn get_all_sets() {
+        let server = MockServer::start().await;
+        mount_sets_page(&server, 1, 5, &[10, 11]).await;
+        mount_sets_page(&server, 2, 5, &[20, 21]).await;
+        mount_sets_page(&server, 3, 5, &[30]).await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let sets = client.get_all_sets(GetSetsParameters::new().page_size(2), 2).await.expect("get_all_sets");
+        let ids: Vec<u64> = sets.iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [10, 11, 20, 21, 30]);
+    }
+
+    #[tokio::test]
+    async fn get_sets_stream() {
+        use futures::TryStreamExt;
+
+        let server = MockServer::start().await;
+        mount_sets_page(&server, 1, 5, &[10, 11]).await;
+        mount_sets_page(&server, 2, 5, &[20, 21]).await;
+        mount_sets_page(&server, 3, 5, &[30]).await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let sets: Vec<_> = client.get_sets_stream(GetSetsParameters::new().page_size(2).page_number(7))
+            .try_collect()
+            .await
+            .expect("get_sets_stream");
+        let ids: Vec<u64> = sets.iter().map(|set| set.set_id).collect();
+        assert_eq!(ids, [10, 11, 20, 21, 30]);
+    }
+
+    #[tokio::test]
+    async fn get_sets_stream_error() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        mount_sets_page(&server, 1, 5, &[10, 11]).await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("pageNumber%22%3A2%7D"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let results: Vec<_> = client.get_sets_stream(GetSetsParameters::new().page_size(2)).collect().await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().expect("set").set_id, 10);
+        assert_eq!(results[1].as_ref().expect("set").set_id, 11);
+        assert!(matches!(results[2], Err(Error::Http { .. })));
+    }
+
+    #[tokio::test]
+    async fn get_sets_updated_since() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("updatedSince%22%3A%222023-06-01%22"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"sets":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let since = chrono::DateTime::parse_from_rfc3339("2023-06-01T23:30:00Z").unwrap().with_timezone(&chrono::Utc);
+        let sets = client.get_sets_updated_since(since, Some(100)).await.expect("get_sets_updated_since");
+        assert_eq!(sets.matches, 0);
+    }
+
+    #[tokio::test]
+    async fn set_collection_many() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/setCollection"))
+            .and(body_string_contains("setID=2&"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"error","message":"Invalid set ID"}"#))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/setCollection"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success"}"#))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        let items = (1..=3).map(|set_id| (set_id, SetCollectionParameters::new().owned(1)));
+        let results = client.set_collection_many_as("abcdef", items, 2).await;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(&results[1], Err(Error::Response(err)) if err.message == "Invalid set ID"));
+        assert!(results[2].is_ok());
+
+        let items = (1..=2).map(|set_id| (set_id, SetCollectionParameters::new().owned(1)));
+        let results = client.set_collection_many(items, 2).await;
+        assert!(results.iter().all(|result| matches!(result, Err(Error::NotLoggedIn))));
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200)
+                .set_body_string(r#"{"status":"success","matches":0,"themes":[]}"#)
+                .set_delay(Duration::from_secs(2)))
+            .mount(&server)
+            .await;
+
+        let client = reqwest::Client::new();
+        let mut client = ClientWrapper::with_endpoint("12345678", &client, endpoint(&server));
+        client.set_timeout(Duration::from_millis(50));
+        assert!(matches!(client.get_themes().await, Err(Error::Timeout)));
+    }
+
+    #[tokio::test]
+    async fn execute_unwrap_response() {
+        use super::{request::GetThemes, reqwest_api::unwrap_response, response::GetThemesResponse};
+
+        let transport = FakeTransport {
+            body: r#"{"status":"error","message":"Invalid API key"}"#,
+            sent: Mutex::default(),
+        };
+        let client = ClientWrapper::new("12345678", &transport);
+        let response = client.execute::<_, GetThemesResponse>(GetThemes::new("12345678")).await.expect("execute");
+        assert!(response.get_err().is_some());
+        assert!(matches!(unwrap_response(response), Err(Error::Response(err)) if err.message == "Invalid API key"));
+    }
+
+    #[tokio::test]
+    async fn check_key_cache() {
+        let transport = FakeTransport {
+            body: r#"{"status":"success"}"#,
+            sent: Mutex::default(),
+        };
+        let mut client = ClientWrapper::new("12345678", &transport);
+        client.set_key_cache_ttl(Duration::from_secs(3600));
+        client.check_key().await.expect("check_key");
+        client.check_key().await.expect("check_key");
+        assert_eq!(transport.sent.lock().unwrap().len(), 1);
+
+        client.invalidate_key_cache();
+        client.check_key().await.expect("check_key");
+        assert_eq!(transport.sent.lock().unwrap().len(), 2);
+
+        client.clear_key_cache_ttl();
+        client.check_key().await.expect("check_key");
+        assert_eq!(transport.sent.lock().unwrap().len(), 3);
+    }
+}
+
+#[cfg(all(test, feature = "blocking"))]
+mod blocking_tests {
+    use super::reqwest_api::{blocking::ClientWrapper, Error, RetryPolicy};
+    use super::request::{self, GetSetsParameters};
+    use std::time::Duration;
+    use url::Url;
+    use wiremock::{matchers::{body_string_contains, method, path}, Mock, MockServer, ResponseTemplate};
+
+    fn endpoint(server: &MockServer) -> Url {
+        Url::parse(&format!("{}/api/v3.asmx/", server.uri())).expect("endpoint")
+    }
+
+    /// Run `f` on a thread where blocking is allowed, since the mock server needs the
+    /// async runtime.
+    async fn blocking<R: Send + 'static>(f: impl FnOnce() -> R + Send + 'static) -> R {
+        tokio::task::spawn_blocking(f).await.expect("spawn_blocking")
+    }
+
+    #[tokio::test]
+    async fn retry_and_quota() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getThemes"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"themes":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let endpoint = endpoint(&server);
+        blocking(move || {
+            let client = reqwest::blocking::Client::new();
+            let client = ClientWrapper::builder("12345678", &client)
+                .endpoint(endpoint)
+                .retry_policy(RetryPolicy::new(3, Duration::from_millis(1)))
+                .daily_limit(2, true)
+                .build()
+                .expect("build");
+            client.get_themes().expect("get_themes");
+            assert_eq!(client.remaining_quota(), Some(0));
+            assert!(matches!(client.get_themes(), Err(Error::QuotaExceeded)));
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn auto_relogin() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=stale"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"error","message":"Invalid user hash"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getUserNotes"))
+            .and(body_string_contains("userHash=fresh"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"userNotes":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/login"))
+            .and(body_string_contains("username=alice"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","hash":"fresh"}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let endpoint = endpoint(&server);
+        blocking(move || {
+            let client = reqwest::blocking::Client::new();
+            let client = ClientWrapper::builder("12345678", &client)
+                .endpoint(endpoint)
+                .user_hash("stale")
+                .auto_relogin("alice", "hunter2")
+                .build()
+                .expect("build");
+            client.get_notes().expect("get_notes");
+            assert_eq!(client.user_hash().as_deref(), Some("fresh"));
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn sets() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("setNumber"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(r#"{{"status":"success","matches":1,"sets":[{}]}}"#, super::response_tests::SET)))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3.asmx/getSets"))
+            .and(body_string_contains("userHash=abcdef"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"status":"success","matches":0,"sets":[]}"#))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let endpoint = endpoint(&server);
+        blocking(move || {
+            let client = reqwest::blocking::Client::new();
+            let client = ClientWrapper::with_endpoint("12345678", &client, endpoint);
+            assert!(matches!(client.get_sets(GetSetsParameters::new().owned_by_user(true)), Err(Error::NotLoggedIn)));
+            assert_eq!(client.resolve_set_id("10497-1").expect("resolve_set_id"), Some(31278));
+            let set = client.get_set_by_number("10497-1", false).expect("get_set_by_number").expect("set");
+            assert_eq!(set.number, "10497");
+            let sets: Vec<_> = client.get_sets_iter(GetSetsParameters::new().full_set_number("10497-1"))
+                .collect::<Result<_, _>>()
+                .expect("get_sets_iter");
+            assert_eq!(sets.len(), 1);
+            let sets = client.get_sets_as("abcdef", GetSetsParameters::new().owned_by_user(true)).expect("get_sets_as");
+            assert_eq!(sets.matches, 0);
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn prepare() {
+        let endpoint = Url::parse("http://localhost:8080/api/v3.asmx/").expect("endpoint");
+        blocking(move || {
+            let client = reqwest::blocking::Client::new();
+            let client = ClientWrapper::builder("12345678", &client)
+                .endpoint(endpoint)
+                .user_hash("abcdef")
+                .timeout(Duration::from_secs(5))
+                .user_agent("my-app/1.0")
+                .build()
+                .expect("build");
+            let request = client.prepare(request::GetUserNotes::new("", "")).expect("prepare");
+            assert_eq!(request.url().as_str(), "http://localhost:8080/api/v3.asmx/getUserNotes");
+            assert_eq!(request.timeout(), Some(&Duration::from_secs(5)));
+            assert_eq!(request.headers().get(reqwest::header::USER_AGENT).expect("user agent"), "my-app/1.0");
+            let body = request.body().and_then(|b| b.as_bytes()).expect("body");
+            assert_eq!(body, b"apiKey=12345678&userHash=abcdef");
+        }).await;
+    }
 }
@@ -1,17 +1,74 @@
 //! Request builders.
 
 use super::util::{self, Flag};
-use chrono::NaiveDate;
-use lazy_static::lazy_static;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::{borrow::Cow, ops::RangeInclusive, sync::LazyLock};
 use url::Url;
 
 #[cfg(feature = "log")]
 use log::warn;
 
-lazy_static! {
-    pub static ref ENDPOINT: url::Url = Url::parse("https://brickset.com/api/v3.asmx/").unwrap();
+#[cfg(feature = "reqwest")]
+use super::reqwest_api::RequestData;
+
+/// The URL of version 3 of the BrickSet API. Method names are joined onto it.
+pub static ENDPOINT: LazyLock<Url> = LazyLock::new(|| Url::parse("https://brickset.com/api/v3.asmx/").unwrap());
+
+/// Value of the `Accept-Encoding` header sent with requests, depending on which of the
+/// `gzip` and `deflate` features are enabled.
+#[cfg(any(feature = "gzip", feature = "deflate"))]
+pub(crate) const ACCEPT_ENCODING: &str = if cfg!(all(feature = "gzip", feature = "deflate")) {
+    "gzip, deflate"
+} else if cfg!(feature = "gzip") {
+    "gzip"
+} else {
+    "deflate"
+};
+
+/// Printed by [`std::fmt::Debug`] in place of API keys, passwords and user hash tokens.
+const REDACTED: &str = "***";
+
+/// Parameters that [`BricksetRequest::to_debug_json`] doesn't reveal.
+const REDACTED_PARAMS: &[&str] = &["apiKey", "password", "userHash"];
+
+/// The earliest year accepted by [`GetSetsParameters::year_range`]. BrickSet's oldest sets
+/// are from 1949.
+pub const FIRST_SET_YEAR: i32 = 1949;
+
+/// The most years that [`GetSetsParameters::year_range`] accepts in one range.
+pub const MAX_YEAR_RANGE_LEN: usize = 100;
+
+/// The method names of every request in this module, as returned by
+/// [`BricksetRequest::method_name`].
+pub const METHOD_NAMES: &[&str] = &[
+    "checkKey",
+    "login",
+    "checkUserHash",
+    "getKeyUsageStats",
+    "getSets",
+    "getAdditionalImages",
+    "getInstructions",
+    "getInstructions2",
+    "getReviews",
+    "getThemes",
+    "getSubthemes",
+    "getYears",
+    "setCollection",
+    "getUserNotes",
+    "getMinifigCollection",
+    "setMinifigCollection",
+    "getUserMinifigNotes",
+];
+
+/// Get the method name from a request URL, such as one built by
+/// [`BricksetRequest::to_request_url`]. The URL's path must be the path of [`ENDPOINT`]
+/// followed by one of [`METHOD_NAMES`]; the host and query are ignored, so URLs pointing
+/// at a mock server or mirror are accepted too.
+pub fn parse_method(url: &Url) -> Option<&'static str> {
+    let method = url.path().strip_prefix(ENDPOINT.path())?;
+    METHOD_NAMES.iter().copied().find(|name| *name == method)
 }
 
 #[derive(Debug)]
@@ -23,78 +80,187 @@ pub enum Error {
     Reqwest(reqwest::Error),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct CheckKey<'s> {
     api_key: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for CheckKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckKey")
+            .field("api_key", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct Login<'s> {
     api_key: &'s str,
     username: &'s str,
     password: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for Login<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Login")
+            .field("api_key", &REDACTED)
+            .field("username", &self.username)
+            .field("password", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct CheckUserHash<'s> {
     api_key: &'s str,
     user_hash: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for CheckUserHash<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CheckUserHash")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetKeyUsageStats<'s> {
     api_key: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetKeyUsageStats<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetKeyUsageStats")
+            .field("api_key", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetSets<'s> {
     api_key: &'s str,
     user_hash: Option<&'s str>,
     params: GetSetsParameters<'s>,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetSets<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetSets")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &self.user_hash.map(|_| REDACTED))
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetAdditionalImages<'s> {
     api_key: &'s str,
     set_id: u64,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetAdditionalImages<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetAdditionalImages")
+            .field("api_key", &REDACTED)
+            .field("set_id", &self.set_id)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetInstructions<'s> {
     api_key: &'s str,
     set_id: u64,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetInstructions<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetInstructions")
+            .field("api_key", &REDACTED)
+            .field("set_id", &self.set_id)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetInstructions2<'s> {
     api_key: &'s str,
     set_number: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetInstructions2<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetInstructions2")
+            .field("api_key", &REDACTED)
+            .field("set_number", &self.set_number)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetReviews<'s> {
     api_key: &'s str,
     set_id: u64,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetReviews<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetReviews")
+            .field("api_key", &REDACTED)
+            .field("set_id", &self.set_id)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetThemes<'s> {
     api_key: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetThemes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetThemes")
+            .field("api_key", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetSubthemes<'s> {
     api_key: &'s str,
     theme: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetSubthemes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetSubthemes")
+            .field("api_key", &REDACTED)
+            .field("theme", &self.theme)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetYears<'s> {
     api_key: &'s str,
     theme: &'s str,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+impl std::fmt::Debug for GetYears<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetYears")
+            .field("api_key", &REDACTED)
+            .field("theme", &self.theme)
+            .finish()
+    }
+}
+
+/// Sort order for `getSets`. Descending variants are sent to the API as the field name
+/// followed by a space and `DESC`, e.g. `OrderBy::NumberDESC` is sent as `"Number DESC"`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum OrderBy {
     Number,
     YearFrom,
@@ -120,32 +286,71 @@ pub enum OrderBy {
     WantCount,
     UserRating,
     CollectionID,
+    #[serde(rename = "Number DESC", alias = "NumberDESC")]
     NumberDESC,
+    #[serde(rename = "YearFrom DESC", alias = "YearFromDESC")]
     YearFromDESC,
+    #[serde(rename = "Pieces DESC", alias = "PiecesDESC")]
     PiecesDESC,
+    #[serde(rename = "Minifigs DESC", alias = "MinifigsDESC")]
     MinifigsDESC,
+    #[serde(rename = "Rating DESC", alias = "RatingDESC")]
     RatingDESC,
+    #[serde(rename = "USRetailPrice DESC", alias = "USRetailPriceDESC")]
     USRetailPriceDESC,
+    #[serde(rename = "UKRetailPrice DESC", alias = "UKRetailPriceDESC")]
     UKRetailPriceDESC,
+    #[serde(rename = "CARetailPrice DESC", alias = "CARetailPriceDESC")]
     CARetailPriceDESC,
+    #[serde(rename = "DERetailPrice DESC", alias = "DERetailPriceDESC")]
     DERetailPriceDESC,
+    #[serde(rename = "FRRetailPrice DESC", alias = "FRRetailPriceDESC")]
     FRRetailPriceDESC,
+    #[serde(rename = "USPricePerPiece DESC", alias = "USPricePerPieceDESC")]
     USPricePerPieceDESC,
+    #[serde(rename = "UKPricePerPiece DESC", alias = "UKPricePerPieceDESC")]
     UKPricePerPieceDESC,
+    #[serde(rename = "CAPricePerPiece DESC", alias = "CAPricePerPieceDESC")]
     CAPricePerPieceDESC,
+    #[serde(rename = "DEPricePerPiece DESC", alias = "DEPricePerPieceDESC")]
     DEPricePerPieceDESC,
+    #[serde(rename = "FRPricePerPiece DESC", alias = "FRPricePerPieceDESC")]
     FRPricePerPieceDESC,
+    #[serde(rename = "Theme DESC", alias = "ThemeDESC")]
     ThemeDESC,
+    #[serde(rename = "Subtheme DESC", alias = "SubthemeDESC")]
     SubthemeDESC,
+    #[serde(rename = "Name DESC", alias = "NameDESC")]
     NameDESC,
+    #[serde(rename = "Random DESC", alias = "RandomDESC")]
     RandomDESC,
+    #[serde(rename = "QtyOwned DESC", alias = "QtyOwnedDESC")]
     QtyOwnedDESC,
+    #[serde(rename = "OwnCount DESC", alias = "OwnCountDESC")]
     OwnCountDESC,
+    #[serde(rename = "WantCount DESC", alias = "WantCountDESC")]
     WantCountDESC,
+    #[serde(rename = "UserRating DESC", alias = "UserRatingDESC")]
     UserRatingDESC,
+    #[serde(rename = "CollectionID DESC", alias = "CollectionIDDESC")]
     CollectionIDDESC,
 }
 
+/// Check a range given to [`GetSetsParameters::year_range`].
+fn check_year_range(years: &RangeInclusive<i32>) -> Result<(), String> {
+    if years.is_empty() {
+        Err(format!("Given year range {years:?} is empty, so no set can match"))
+    } else if *years.start() < FIRST_SET_YEAR {
+        Err(format!("Given year range {years:?} starts before {FIRST_SET_YEAR}"))
+    } else if i64::from(*years.end()) - i64::from(*years.start()) >= MAX_YEAR_RANGE_LEN as i64 {
+        Err(format!("Given year range {years:?} covers more than {MAX_YEAR_RANGE_LEN} years"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Parameters for a `getSets` request. Text parameters can be given as `&str`s, or as
+/// `String`s when they are built at runtime; see [`Self::into_owned`].
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GetSetsParameters<'s> {
@@ -153,24 +358,39 @@ pub struct GetSetsParameters<'s> {
     #[serde(default)]
     set_id: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
     #[serde(default)]
-    query: Option<&'s str>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<Cow<'s, str>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(with = "util::str_vec_as_commastr")]
+    #[serde(borrow)]
     #[serde(default)]
-    theme: Option<&'s str>,
+    theme: Vec<Cow<'s, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
     #[serde(default)]
-    subtheme: Option<&'s str>,
+    subtheme: Option<Cow<'s, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
     #[serde(default)]
     #[serde(rename = "setNumber")]
-    full_set_number: Option<&'s str>,
+    full_set_number: Option<Cow<'s, str>>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(with = "util::int_vec_as_commastr")]
+    #[serde(default)]
     year: Vec<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
     #[serde(default)]
-    tag: Option<&'s str>,
+    tag: Option<Cow<'s, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
+    #[serde(default)]
+    category: Option<Cow<'s, str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(borrow)]
+    #[serde(default)]
+    packaging_type: Option<Cow<'s, str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     owned: Option<Flag>,
@@ -193,9 +413,12 @@ pub struct GetSetsParameters<'s> {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     extended_data: Option<Flag>,
+    /// Set by [`Self::year_range`] when given a range that it rejects.
+    #[serde(skip)]
+    year_range_error: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SetCollection<'s> {
     api_key: &'s str,
     user_hash: &'s str,
@@ -203,6 +426,17 @@ pub struct SetCollection<'s> {
     params: SetCollectionParameters<'s>,
 }
 
+impl std::fmt::Debug for SetCollection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SetCollection")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .field("set_id", &self.set_id)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SetCollectionParameters<'s> {
@@ -218,19 +452,38 @@ pub struct SetCollectionParameters<'s> {
     rating: Option<i32>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct GetUserNotes<'s> {
     api_key: &'s str,
     user_hash: &'s str,
 }
 
-#[derive(Debug, Clone)]
+impl std::fmt::Debug for GetUserNotes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetUserNotes")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
 pub struct GetMinifigCollection<'s> {
     api_key: &'s str,
     user_hash: &'s str,
     params: GetMinifigCollectionParameters<'s>,
 }
 
+impl std::fmt::Debug for GetMinifigCollection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetMinifigCollection")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMinifigCollectionParameters<'s> {
@@ -242,7 +495,7 @@ pub struct GetMinifigCollectionParameters<'s> {
     query: Option<&'s str>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SetMinifigCollection<'s> {
     api_key: &'s str,
     user_hash: &'s str,
@@ -250,6 +503,17 @@ pub struct SetMinifigCollection<'s> {
     params: SetMinifigCollectionParameters<'s>,
 }
 
+impl std::fmt::Debug for SetMinifigCollection<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SetMinifigCollection")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .field("minifig_number", &self.minifig_number)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SetMinifigCollectionParameters<'s> {
@@ -263,12 +527,36 @@ pub struct SetMinifigCollectionParameters<'s> {
     notes: Option<&'s str>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct GetUserMinifigNotes<'s> {
     api_key: &'s str,
     user_hash: &'s str
 }
 
+impl std::fmt::Debug for GetUserMinifigNotes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GetUserMinifigNotes")
+            .field("api_key", &REDACTED)
+            .field("user_hash", &REDACTED)
+            .finish()
+    }
+}
+
+/// BrickSet's internal ID for a set, as returned by [`crate::response::Set::id`]. Unlike
+/// [`crate::response::Set`], [`SetId`] implements [`Eq`] and [`Hash`], so it can be used to
+/// deduplicate sets with a `HashSet` or `HashMap`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct SetId(pub u64);
+
+/// A set number, optionally including the variant number. For example, `"6876-1"` is
+/// set number `"6876"`, variant 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SetNumber {
+    pub number: String,
+    pub variant: Option<u32>,
+}
+
 impl<'s> CheckKey<'s> {
     pub fn new(api_key: &'s str) -> Self {
         CheckKey { api_key }
@@ -378,6 +666,52 @@ impl<'s> GetSetsParameters<'s> {
         Self::default()
     }
 
+    /// The 20 most recent sets, newest first. Same as
+    /// `GetSetsParameters::new().order_by(OrderBy::YearFromDESC).page_size(20)`.
+    ///
+    /// BrickSet can't filter by release status, so sets that have been announced but not yet
+    /// released may be included. Check [`crate::response::Set::released`] to skip them.
+    #[inline]
+    pub fn latest() -> Self {
+        Self::new()
+            .order_by(OrderBy::YearFromDESC)
+            .page_size(20)
+    }
+
+    /// Every set in `theme`, sorted by set number. Same as
+    /// `GetSetsParameters::new().theme(theme).order_by(OrderBy::Number)`.
+    #[inline]
+    pub fn for_theme(theme: impl Into<Cow<'s, str>>) -> Self {
+        Self::new()
+            .theme(theme)
+            .order_by(OrderBy::Number)
+    }
+
+    /// Copy any borrowed text parameters, so the parameters no longer borrow anything.
+    pub fn into_owned(self) -> GetSetsParameters<'static> {
+        let owned = |text: Option<Cow<'s, str>>| text.map(|text| Cow::Owned(text.into_owned()));
+
+        GetSetsParameters {
+            set_id: self.set_id,
+            query: owned(self.query),
+            theme: self.theme.into_iter().map(|theme| Cow::Owned(theme.into_owned())).collect(),
+            subtheme: owned(self.subtheme),
+            full_set_number: owned(self.full_set_number),
+            year: self.year,
+            tag: owned(self.tag),
+            category: owned(self.category),
+            packaging_type: owned(self.packaging_type),
+            owned: self.owned,
+            wanted: self.wanted,
+            updated_since: self.updated_since,
+            order_by: self.order_by,
+            page_size: self.page_size,
+            page_number: self.page_number,
+            extended_data: self.extended_data,
+            year_range_error: self.year_range_error,
+        }
+    }
+
     /// Filter results to show only a single set ID.
     #[inline]
     pub fn set_id(mut self, set_id: u64) -> Self {
@@ -390,6 +724,7 @@ impl<'s> GetSetsParameters<'s> {
     pub fn year(mut self, year: i32) -> Self {
         self.year.clear();
         self.year.push(year);
+        self.year_range_error = None;
         self
     }
 
@@ -397,41 +732,93 @@ impl<'s> GetSetsParameters<'s> {
     #[inline]
     pub fn years(mut self, year: Vec<i32>) -> Self {
         self.year = year;
+        self.year_range_error = None;
+        self
+    }
+
+    /// Filter results to show sets from every year in `years`. The range is rejected if it
+    /// is empty (e.g. `2000..=1990`), starts before [`FIRST_SET_YEAR`], or covers more than
+    /// [`MAX_YEAR_RANGE_LEN`] years. A rejected range makes [`Self::validate`] return an
+    /// error, and so does encoding a [`GetSets`] request with these parameters. The year
+    /// filter is never silently dropped.
+    #[inline]
+    pub fn year_range(mut self, years: RangeInclusive<i32>) -> Self {
+        match check_year_range(&years) {
+            Ok(()) => {
+                self.year = years.collect();
+                self.year_range_error = None;
+            },
+            Err(message) => {
+                #[cfg(feature = "log")]
+                warn!("{message}");
+                self.year.clear();
+                self.year_range_error = Some(message);
+            },
+        }
         self
     }
 
+    /// Same as [`Self::year_range`], but returns an error if the range is rejected.
+    #[inline]
+    pub fn try_year_range(self, years: RangeInclusive<i32>) -> Result<Self, Error> {
+        check_year_range(&years).map_err(Error::Message)?;
+        Ok(self.year_range(years))
+    }
+
     /// Query text. Searches set number, name, theme, and subtheme.
     #[inline]
-    pub fn query(mut self, query: &'s str) -> Self {
-        self.query = Some(query);
+    pub fn query(mut self, query: impl Into<Cow<'s, str>>) -> Self {
+        self.query = Some(query.into());
         self
     }
 
     /// Filter results to show sets from a single theme.
     #[inline]
-    pub fn theme(mut self, theme: &'s str) -> Self {
-        self.theme = Some(theme);
+    pub fn theme(mut self, theme: impl Into<Cow<'s, str>>) -> Self {
+        self.theme.clear();
+        self.theme.push(theme.into());
+        self
+    }
+
+    /// Filter results to show sets from any of several themes.
+    #[inline]
+    pub fn themes<S: Into<Cow<'s, str>>>(mut self, themes: impl IntoIterator<Item = S>) -> Self {
+        self.theme = themes.into_iter().map(Into::into).collect();
         self
     }
 
     /// Filter results to show sets from a single sub-theme.
     #[inline]
-    pub fn subtheme(mut self, subtheme: &'s str) -> Self {
-        self.subtheme = Some(subtheme);
+    pub fn subtheme(mut self, subtheme: impl Into<Cow<'s, str>>) -> Self {
+        self.subtheme = Some(subtheme.into());
         self
     }
 
     /// Full set number, including the variant number. ex: "6876-1"
     #[inline]
-    pub fn full_set_number(mut self, full_set_number: &'s str) -> Self {
-        self.full_set_number = Some(full_set_number);
+    pub fn full_set_number(mut self, full_set_number: impl Into<Cow<'s, str>>) -> Self {
+        self.full_set_number = Some(full_set_number.into());
         self
     }
 
     /// Filter results to show sets with a particular tag.
     #[inline]
-    pub fn tag(mut self, tag: &'s str) -> Self {
-        self.tag = Some(tag);
+    pub fn tag(mut self, tag: impl Into<Cow<'s, str>>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Filter results to show sets in a particular category. ex: "Normal", "Gear"
+    #[inline]
+    pub fn category(mut self, category: impl Into<Cow<'s, str>>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Filter results to show sets with a particular packaging type. ex: "Box", "Polybag"
+    #[inline]
+    pub fn packaging_type(mut self, packaging_type: impl Into<Cow<'s, str>>) -> Self {
+        self.packaging_type = Some(packaging_type.into());
         self
     }
 
@@ -463,6 +850,13 @@ impl<'s> GetSetsParameters<'s> {
         self
     }
 
+    /// Same as [`Self::updated_since`], but takes a timestamp. Only the date (UTC) is sent
+    /// to BrickSet, so sets updated earlier on the same day are included.
+    #[inline]
+    pub fn updated_since_datetime(self, datetime: DateTime<Utc>) -> Self {
+        self.updated_since(datetime.date_naive())
+    }
+
     /// Sort the results.
     #[inline]
     pub fn order_by(mut self, order_by: OrderBy) -> Self {
@@ -485,6 +879,66 @@ impl<'s> GetSetsParameters<'s> {
         self
     }
 
+    /// Same as [`Self::page_size`], but returns an error if `page_size` is zero or greater
+    /// than 500.
+    #[inline]
+    pub fn try_page_size(self, page_size: usize) -> Result<Self, Error> {
+        if page_size > 500 {
+            Err(Error::Message(format!("Given page_size was {page_size}, but the maximum is 500")))
+        } else if page_size == 0 {
+            Err(Error::Message("Zero page size is not valid".to_string()))
+        } else {
+            Ok(self.page_size(page_size))
+        }
+    }
+
+    /// The JSON string sent as the `params` parameter of a `getSets` request, exactly as
+    /// [`GetSets`] encodes it.
+    #[inline]
+    pub fn to_params_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Returns true if the parameters filter by the user's owned or wanted sets, which
+    /// requires a user hash token.
+    #[inline]
+    pub fn requires_user_hash(&self) -> bool {
+        self.owned.is_some() || self.wanted.is_some()
+    }
+
+    /// Check the parameters for values that BrickSet would reject: a page size outside of
+    /// 1-500, a page number of zero, a set ID combined with a query or set number, or a
+    /// rejected [`Self::year_range`].
+    pub fn validate(&self) -> Result<(), Error> {
+        self.check_year_range()?;
+        if let Some(page_size) = self.page_size {
+            if page_size > 500 {
+                return Err(Error::Message(format!("Given page_size was {page_size}, but the maximum is 500")));
+            } else if page_size == 0 {
+                return Err(Error::Message("Zero page size is not valid".to_string()));
+            }
+        }
+        if self.page_number == Some(0) {
+            return Err(Error::Message("Page numbers start at 1".to_string()));
+        }
+        if self.set_id.is_some() {
+            if self.query.is_some() {
+                return Err(Error::Message("set_id cannot be combined with a query".to_string()));
+            }
+            if self.full_set_number.is_some() {
+                return Err(Error::Message("set_id cannot be combined with a set number".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_year_range(&self) -> Result<(), Error> {
+        match &self.year_range_error {
+            Some(message) => Err(Error::Message(message.clone())),
+            None => Ok(()),
+        }
+    }
+
     /// Specify which page of sets to retrieve. Should be used in conjunction with 
     /// [`Self::page_size`]. Default = 1
     #[inline]
@@ -494,13 +948,112 @@ impl<'s> GetSetsParameters<'s> {
     }
 }
 
+/// Accessors for the values set using the builder functions.
+impl<'s> GetSetsParameters<'s> {
+    /// The value set with [`Self::set_id`].
+    #[inline]
+    pub fn get_set_id(&self) -> Option<u64> {
+        self.set_id
+    }
+
+    /// The value set with [`Self::years`].
+    #[inline]
+    pub fn get_years(&self) -> &[i32] {
+        &self.year
+    }
+
+    /// The value set with [`Self::query`].
+    #[inline]
+    pub fn get_query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// The value set with [`Self::themes`].
+    #[inline]
+    pub fn get_themes(&self) -> &[Cow<'s, str>] {
+        &self.theme
+    }
+
+    /// The value set with [`Self::subtheme`].
+    #[inline]
+    pub fn get_subtheme(&self) -> Option<&str> {
+        self.subtheme.as_deref()
+    }
+
+    /// The value set with [`Self::full_set_number`].
+    #[inline]
+    pub fn get_full_set_number(&self) -> Option<&str> {
+        self.full_set_number.as_deref()
+    }
+
+    /// The value set with [`Self::tag`].
+    #[inline]
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// The value set with [`Self::category`].
+    #[inline]
+    pub fn get_category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// The value set with [`Self::packaging_type`].
+    #[inline]
+    pub fn get_packaging_type(&self) -> Option<&str> {
+        self.packaging_type.as_deref()
+    }
+
+    /// The value set with [`Self::owned_by_user`].
+    #[inline]
+    pub fn get_owned_by_user(&self) -> bool {
+        self.owned.is_some()
+    }
+
+    /// The value set with [`Self::wanted_by_user`].
+    #[inline]
+    pub fn get_wanted_by_user(&self) -> bool {
+        self.wanted.is_some()
+    }
+
+    /// The value set with [`Self::extended_data`].
+    #[inline]
+    pub fn get_extended_data(&self) -> bool {
+        self.extended_data.is_some()
+    }
+
+    /// The value set with [`Self::updated_since`].
+    #[inline]
+    pub fn get_updated_since(&self) -> Option<NaiveDate> {
+        self.updated_since
+    }
+
+    /// The value set with [`Self::order_by`].
+    #[inline]
+    pub fn get_order_by(&self) -> Option<OrderBy> {
+        self.order_by
+    }
+
+    /// The value set with [`Self::page_size`].
+    #[inline]
+    pub fn get_page_size(&self) -> Option<usize> {
+        self.page_size
+    }
+
+    /// The value set with [`Self::page_number`].
+    #[inline]
+    pub fn get_page_number(&self) -> Option<usize> {
+        self.page_number
+    }
+}
+
 impl<'s> SetCollectionParameters<'s> {
     /// new, empty SetCollectionParameters. These parameters will not alter BrickSet's database.
     /// To alter a database entry, use these functions:
     /// - [`Self::owned`]
     /// - [`Self::wanted`]
-    /// - [`Self::notes`]
-    /// - [`Self::rating`]
+    /// - [`Self::notes`] or [`Self::clear_notes`]
+    /// - [`Self::rating`] or [`Self::clear_rating`]
     pub fn new() -> Self {
         Self::default()
     }
@@ -527,17 +1080,32 @@ impl<'s> SetCollectionParameters<'s> {
         self
     }
 
-    /// Alter the user's notes for the set.
+    /// Alter the user's notes for the set. If neither this nor [`Self::clear_notes`] is
+    /// used, the notes are left unchanged.
     pub fn notes(mut self, notes: &'s str) -> Self {
         self.notes = Some(notes);
         self
     }
 
-    /// Alter the user's rating of the set.
+    /// Remove the user's notes for the set, by sending empty notes.
+    pub fn clear_notes(mut self) -> Self {
+        self.notes = Some("");
+        self
+    }
+
+    /// Alter the user's rating of the set, from 1 to 5. If neither this nor
+    /// [`Self::clear_rating`] is used, the rating is left unchanged.
     pub fn rating(mut self, rating: i32) -> Self {
         self.rating = Some(rating);
         self
     }
+
+    /// Remove the user's rating of the set, by sending a rating of 0, which BrickSet treats
+    /// as unrated.
+    pub fn clear_rating(mut self) -> Self {
+        self.rating = Some(0);
+        self
+    }
 }
 
 impl<'s> GetUserNotes<'s> {
@@ -564,6 +1132,9 @@ impl<'s> GetMinifigCollectionParameters<'s> {
     }
 
     /// Add query text to the request. The query will search by minifig ID number, and name.
+    ///
+    /// BrickSet can't filter minifigs by category. To do that, use
+    /// [`crate::response::GetMinifigCollectionResponse::filter_by_category`].
     pub fn query(mut self, query: &'s str) -> Self {
         self.query = Some(query);
         self
@@ -622,7 +1193,175 @@ impl<'s> GetUserMinifigNotes<'s> {
     }
 }
 
+impl From<u64> for SetId {
+    fn from(value: u64) -> Self {
+        SetId(value)
+    }
+}
+
+impl From<SetId> for u64 {
+    fn from(value: SetId) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for SetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl SetNumber {
+    pub fn new(number: &str, variant: Option<u32>) -> Self {
+        SetNumber { number: number.to_string(), variant }
+    }
+
+    /// The full set number, including the variant number. If the variant is unknown, the
+    /// first variant is assumed. For example, both `"6876"` and `"6876-1"` become `"6876-1"`.
+    pub fn full(&self) -> String {
+        format!("{}-{}", self.number, self.variant.unwrap_or(1))
+    }
+}
+
+impl std::fmt::Display for SetNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.variant {
+            Some(variant) => write!(f, "{}-{variant}", self.number),
+            None => f.write_str(&self.number),
+        }
+    }
+}
+
+impl std::str::FromStr for SetNumber {
+    type Err = Error;
+
+    /// Parse a set number like `"6876"` or `"6876-1"`. Whitespace around the number and
+    /// the variant is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, variant) = match s.split_once('-') {
+            Some((number, variant)) => {
+                let variant = variant.trim().parse::<u32>()
+                    .map_err(|_| Error::Message(format!("Invalid variant in set number \"{s}\"")))?;
+                (number.trim(), Some(variant))
+            }
+            None => (s.trim(), None),
+        };
+
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::Message(format!("Invalid set number \"{s}\"")));
+        }
+
+        Ok(SetNumber::new(number, variant))
+    }
+}
+
 impl OrderBy {
+    /// Every [`OrderBy`] value.
+    pub const ALL: &'static [OrderBy] = &[
+        OrderBy::Number,
+        OrderBy::YearFrom,
+        OrderBy::Pieces,
+        OrderBy::Minifigs,
+        OrderBy::Rating,
+        OrderBy::USRetailPrice,
+        OrderBy::UKRetailPrice,
+        OrderBy::CARetailPrice,
+        OrderBy::DERetailPrice,
+        OrderBy::FRRetailPrice,
+        OrderBy::USPricePerPiece,
+        OrderBy::UKPricePerPiece,
+        OrderBy::CAPricePerPiece,
+        OrderBy::DEPricePerPiece,
+        OrderBy::FRPricePerPiece,
+        OrderBy::Theme,
+        OrderBy::Subtheme,
+        OrderBy::Name,
+        OrderBy::Random,
+        OrderBy::QtyOwned,
+        OrderBy::OwnCount,
+        OrderBy::WantCount,
+        OrderBy::UserRating,
+        OrderBy::CollectionID,
+        OrderBy::NumberDESC,
+        OrderBy::YearFromDESC,
+        OrderBy::PiecesDESC,
+        OrderBy::MinifigsDESC,
+        OrderBy::RatingDESC,
+        OrderBy::USRetailPriceDESC,
+        OrderBy::UKRetailPriceDESC,
+        OrderBy::CARetailPriceDESC,
+        OrderBy::DERetailPriceDESC,
+        OrderBy::FRRetailPriceDESC,
+        OrderBy::USPricePerPieceDESC,
+        OrderBy::UKPricePerPieceDESC,
+        OrderBy::CAPricePerPieceDESC,
+        OrderBy::DEPricePerPieceDESC,
+        OrderBy::FRPricePerPieceDESC,
+        OrderBy::ThemeDESC,
+        OrderBy::SubthemeDESC,
+        OrderBy::NameDESC,
+        OrderBy::RandomDESC,
+        OrderBy::QtyOwnedDESC,
+        OrderBy::OwnCountDESC,
+        OrderBy::WantCountDESC,
+        OrderBy::UserRatingDESC,
+        OrderBy::CollectionIDDESC,
+    ];
+
+    /// The canonical name of the [`OrderBy`], e.g. `"PiecesDESC"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OrderBy::Number => "Number",
+            OrderBy::YearFrom => "YearFrom",
+            OrderBy::Pieces => "Pieces",
+            OrderBy::Minifigs => "Minifigs",
+            OrderBy::Rating => "Rating",
+            OrderBy::USRetailPrice => "USRetailPrice",
+            OrderBy::UKRetailPrice => "UKRetailPrice",
+            OrderBy::CARetailPrice => "CARetailPrice",
+            OrderBy::DERetailPrice => "DERetailPrice",
+            OrderBy::FRRetailPrice => "FRRetailPrice",
+            OrderBy::USPricePerPiece => "USPricePerPiece",
+            OrderBy::UKPricePerPiece => "UKPricePerPiece",
+            OrderBy::CAPricePerPiece => "CAPricePerPiece",
+            OrderBy::DEPricePerPiece => "DEPricePerPiece",
+            OrderBy::FRPricePerPiece => "FRPricePerPiece",
+            OrderBy::Theme => "Theme",
+            OrderBy::Subtheme => "Subtheme",
+            OrderBy::Name => "Name",
+            OrderBy::Random => "Random",
+            OrderBy::QtyOwned => "QtyOwned",
+            OrderBy::OwnCount => "OwnCount",
+            OrderBy::WantCount => "WantCount",
+            OrderBy::UserRating => "UserRating",
+            OrderBy::CollectionID => "CollectionID",
+            OrderBy::NumberDESC => "NumberDESC",
+            OrderBy::YearFromDESC => "YearFromDESC",
+            OrderBy::PiecesDESC => "PiecesDESC",
+            OrderBy::MinifigsDESC => "MinifigsDESC",
+            OrderBy::RatingDESC => "RatingDESC",
+            OrderBy::USRetailPriceDESC => "USRetailPriceDESC",
+            OrderBy::UKRetailPriceDESC => "UKRetailPriceDESC",
+            OrderBy::CARetailPriceDESC => "CARetailPriceDESC",
+            OrderBy::DERetailPriceDESC => "DERetailPriceDESC",
+            OrderBy::FRRetailPriceDESC => "FRRetailPriceDESC",
+            OrderBy::USPricePerPieceDESC => "USPricePerPieceDESC",
+            OrderBy::UKPricePerPieceDESC => "UKPricePerPieceDESC",
+            OrderBy::CAPricePerPieceDESC => "CAPricePerPieceDESC",
+            OrderBy::DEPricePerPieceDESC => "DEPricePerPieceDESC",
+            OrderBy::FRPricePerPieceDESC => "FRPricePerPieceDESC",
+            OrderBy::ThemeDESC => "ThemeDESC",
+            OrderBy::SubthemeDESC => "SubthemeDESC",
+            OrderBy::NameDESC => "NameDESC",
+            OrderBy::RandomDESC => "RandomDESC",
+            OrderBy::QtyOwnedDESC => "QtyOwnedDESC",
+            OrderBy::OwnCountDESC => "OwnCountDESC",
+            OrderBy::WantCountDESC => "WantCountDESC",
+            OrderBy::UserRatingDESC => "UserRatingDESC",
+            OrderBy::CollectionIDDESC => "CollectionIDDESC",
+        }
+    }
+
     /// Get the reversed version of an [`OrderBy`]. For example,
     /// `OrderBy::Number.reversed()` will return `OrderBy::NumberDESC`,
     /// and vice versa.
@@ -678,12 +1417,79 @@ impl OrderBy {
             OrderBy::CollectionIDDESC => OrderBy::CollectionID,
         }
     }
+
+    /// Returns true if the [`OrderBy`] is a descending sort, e.g. `OrderBy::NumberDESC`.
+    pub fn is_descending(self) -> bool {
+        matches!(
+            self,
+            OrderBy::NumberDESC
+                | OrderBy::YearFromDESC
+                | OrderBy::PiecesDESC
+                | OrderBy::MinifigsDESC
+                | OrderBy::RatingDESC
+                | OrderBy::USRetailPriceDESC
+                | OrderBy::UKRetailPriceDESC
+                | OrderBy::CARetailPriceDESC
+                | OrderBy::DERetailPriceDESC
+                | OrderBy::FRRetailPriceDESC
+                | OrderBy::USPricePerPieceDESC
+                | OrderBy::UKPricePerPieceDESC
+                | OrderBy::CAPricePerPieceDESC
+                | OrderBy::DEPricePerPieceDESC
+                | OrderBy::FRPricePerPieceDESC
+                | OrderBy::ThemeDESC
+                | OrderBy::SubthemeDESC
+                | OrderBy::NameDESC
+                | OrderBy::RandomDESC
+                | OrderBy::QtyOwnedDESC
+                | OrderBy::OwnCountDESC
+                | OrderBy::WantCountDESC
+                | OrderBy::UserRatingDESC
+                | OrderBy::CollectionIDDESC
+        )
+    }
+
+    /// Get the ascending version of an [`OrderBy`]. For example, `OrderBy::NumberDESC.base()`
+    /// will return `OrderBy::Number`. Ascending values are returned unchanged.
+    pub fn base(self) -> Self {
+        if self.is_descending() {
+            self.reversed()
+        } else {
+            self
+        }
+    }
+}
+
+impl std::fmt::Display for OrderBy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for OrderBy {
+    type Err = Error;
+
+    /// Parse an [`OrderBy`] from its canonical name, or from the spelling sent to the API.
+    /// Case-insensitive, so `"piecesdesc"`, `"PiecesDESC"` and `"Pieces DESC"` are all
+    /// [`OrderBy::PiecesDESC`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spaced = s.split_once(' ').filter(|(_, desc)| desc.eq_ignore_ascii_case("DESC"));
+        OrderBy::ALL
+            .iter()
+            .copied()
+            .find(|order_by| match spaced {
+                Some((base, _)) => order_by.is_descending() && order_by.base().as_str().eq_ignore_ascii_case(base),
+                None => order_by.as_str().eq_ignore_ascii_case(s),
+            })
+            .ok_or_else(|| Error::Message(format!("Unknown sort order \"{s}\"")))
+    }
 }
 
 /// Implemented by any type that can be turned into a BrickSet API request.
 ///
 /// - [`BricksetRequest::to_request_url`] creates a URL containing the request method and query parameters.
 /// - [`BricksetRequest::to_reqwest`] creates a POST [`reqwest::Request`] with the query paramters url-encoded in the body.
+/// - `BricksetRequest::to_blocking_reqwest` does the same for [`reqwest::blocking`] (requires the `blocking` feature).
 pub trait BricksetRequest {
     /// Encode method parameters via a URL serializer.
     fn encode_query<T>(&self, query: &mut url::form_urlencoded::Serializer<T>) -> Result<(), Error>
@@ -693,6 +1499,12 @@ pub trait BricksetRequest {
     /// The request's method name.
     fn method_name(&self) -> &'static str;
 
+    /// Returns false if sending the request more than once could alter BrickSet's database
+    /// more than once. Requests that return false are never retried automatically.
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     /// Create a URL representing the request. All request parameters will appear in the URL.
     /// 
     /// NOTE: It is better practice to only put the method name in the request URL, and use
@@ -706,25 +1518,83 @@ pub trait BricksetRequest {
     /// Build a [`reqwest::Request`] from `self`. The resulting [`reqwest::Request`] will
     /// always be a POST request with all method parameters encoded into the body, using
     /// content type `application/x-www-form-urlencoded`.
+    ///
+    /// When the `gzip` or `deflate` feature is enabled, the request also asks BrickSet for
+    /// a compressed response via `Accept-Encoding`. These features enable the corresponding
+    /// reqwest features, so the response is decompressed transparently.
     #[cfg(feature = "reqwest")]
     fn to_reqwest(&self, client: &reqwest::Client) -> Result<reqwest::Request, Error> {
-        let url = ENDPOINT.join(self.method_name())?;
+        self.to_reqwest_with_endpoint(client, &ENDPOINT)
+    }
 
-        let mut body = url::form_urlencoded::Serializer::new(String::new());
+    /// Same as [`Self::to_reqwest`], but sends the request to `endpoint` instead of [`ENDPOINT`].
+    /// `endpoint` should end with a trailing slash, e.g. `http://localhost:8080/api/v3.asmx/`.
+    #[cfg(feature = "reqwest")]
+    fn to_reqwest_with_endpoint(&self, client: &reqwest::Client, endpoint: &Url) -> Result<reqwest::Request, Error> {
+        Ok(RequestData::form(self, endpoint)?.build(client)?)
+    }
 
+    /// Encode method parameters as a JSON object. Every value is a string, exactly as it
+    /// would appear in a form-encoded body, so [`GetSets`]'s `params` remains a JSON-encoded
+    /// string rather than a nested object.
+    fn encode_json(&self) -> Result<serde_json::Map<String, serde_json::Value>, Error> {
+        let mut body = url::form_urlencoded::Serializer::new(String::new());
         self.encode_query(&mut body)?;
-
         let body = body.finish();
 
-        Ok(client
-            .post(url)
-            .header(
-                reqwest::header::CONTENT_TYPE,
-                "application/x-www-form-urlencoded",
-            )
-            .header(reqwest::header::CONTENT_LENGTH, body.as_bytes().len())
-            .body(body)
-            .build()?)
+        Ok(url::form_urlencoded::parse(body.as_bytes())
+            .map(|(k, v)| (k.into_owned(), serde_json::Value::String(v.into_owned())))
+            .collect())
+    }
+
+    /// The method parameters as a JSON object, for logging. This is the same as
+    /// [`Self::encode_json`], except the API key, password and user hash are replaced with
+    /// `"***"` unless they are empty. If the parameters can't be encoded, the object holds an `error` message instead.
+    fn to_debug_json(&self) -> serde_json::Value {
+        match self.encode_json() {
+            Ok(mut params) => {
+                for key in REDACTED_PARAMS {
+                    if let Some(value) = params.get_mut(*key).filter(|value| *value != "") {
+                        *value = serde_json::Value::String(REDACTED.to_string());
+                    }
+                }
+                serde_json::Value::Object(params)
+            },
+            Err(err) => serde_json::json!({ "error": err.to_string() }),
+        }
+    }
+
+    /// Same as [`Self::to_reqwest`], but encodes all method parameters into a JSON object
+    /// using content type `application/json`. This is mostly useful for debugging, or for
+    /// proxies that log request bodies.
+    ///
+    /// BrickSet only documents form-encoded parameters, for every method, which is what
+    /// [`Self::to_reqwest`] sends. It isn't known whether BrickSet accepts JSON
+    /// bodies, or what it responds with if it does; ASP.NET services often wrap JSON responses
+    /// in a `d` property, which [`crate::Response`] can't parse. Send requests with
+    /// [`Self::to_reqwest`] unless you have checked that JSON works for your use case.
+    #[cfg(feature = "reqwest")]
+    fn to_reqwest_json(&self, client: &reqwest::Client) -> Result<reqwest::Request, Error> {
+        self.to_reqwest_json_with_endpoint(client, &ENDPOINT)
+    }
+
+    /// Same as [`Self::to_reqwest_json`], but sends the request to `endpoint` instead of
+    /// [`ENDPOINT`].
+    #[cfg(feature = "reqwest")]
+    fn to_reqwest_json_with_endpoint(&self, client: &reqwest::Client, endpoint: &Url) -> Result<reqwest::Request, Error> {
+        Ok(RequestData::json(self, endpoint)?.build(client)?)
+    }
+
+    /// Same as [`Self::to_reqwest`], but builds a [`reqwest::blocking::Request`].
+    #[cfg(feature = "blocking")]
+    fn to_blocking_reqwest(&self, client: &reqwest::blocking::Client) -> Result<reqwest::blocking::Request, Error> {
+        self.to_blocking_reqwest_with_endpoint(client, &ENDPOINT)
+    }
+
+    /// Same as [`Self::to_reqwest_with_endpoint`], but builds a [`reqwest::blocking::Request`].
+    #[cfg(feature = "blocking")]
+    fn to_blocking_reqwest_with_endpoint(&self, client: &reqwest::blocking::Client, endpoint: &Url) -> Result<reqwest::blocking::Request, Error> {
+        Ok(RequestData::form(self, endpoint)?.build_blocking(client)?)
     }
 }
 
@@ -794,9 +1664,10 @@ impl<'s> BricksetRequest for GetSets<'s> {
     where
         T: url::form_urlencoded::Target,
     {
-        let params = serde_json::to_string(&self.params)?;
+        self.params.check_year_range()?;
+        let params = self.params.to_params_json()?;
 
-        if (self.params.wanted.is_some() || self.params.owned.is_some()) && self.user_hash.is_none() {
+        if self.params.requires_user_hash() && self.user_hash.is_none() {
             #[cfg(feature = "log")]
             warn!("User hash is required when wanted/owned parameters are used in GetSets");
         }
@@ -941,6 +1812,10 @@ impl<'s> BricksetRequest for SetCollection<'s> {
     fn method_name(&self) -> &'static str {
         "setCollection"
     }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 impl<'s> BricksetRequest for GetUserNotes<'s> {
@@ -996,6 +1871,10 @@ impl<'s> BricksetRequest for SetMinifigCollection<'s> {
     fn method_name(&self) -> &'static str {
         "setMinifigCollection"
     }
+
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
 
 impl<'s> BricksetRequest for GetUserMinifigNotes<'s> {
@@ -1036,7 +1915,7 @@ impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::UrlParseError(err) => err.fmt(f),
-            Error::Message(err) => f.write_str(&err),
+            Error::Message(err) => f.write_str(err),
             Error::SerdeJson(err) => err.fmt(f),
             #[cfg(feature = "reqwest")]
             Error::Reqwest(err) => err.fmt(f),
@@ -34,24 +34,283 @@
 //! ```
 //! 
 
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use reqwest::Client;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json;
+use std::{future::Future, sync::{Arc, Mutex, RwLock}, time::{Duration, Instant}};
+use url::Url;
 
 #[cfg(feature = "log")]
-use log::debug;
+use log::{debug, warn};
 
 use super::{Response, response, request::{self, BricksetRequest, GetMinifigCollectionParameters, SetMinifigCollectionParameters}};
 
-type Result<T, E = Error> = std::result::Result<T, E>;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "record")]
+pub mod record;
 
-type RespResult<T> = std::result::Result<T, response::Error>;
+type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Wraps a [`reqwest::Client`] with convenient functions for accessing the
 /// BrickSet API, including rudimentary session management.
-pub struct ClientWrapper<'a> {
-    client: &'a Client,
+///
+/// All request functions take `&self`, so a single [`ClientWrapper`] can be shared between
+/// tasks and threads, and used to send concurrent requests. Functions ending in `_as` take
+/// a user hash token as a parameter instead of using the [`ClientWrapper`]'s saved token,
+/// which lets one [`ClientWrapper`] serve requests on behalf of several users at once.
+/// Logging in and out require `&mut self`, so the saved token can't change while requests
+/// are in flight, unless automatic re-login is enabled with [`Self::set_auto_relogin`].
+///
+/// Requests are sent through a [`Transport`], which is a [`reqwest::Client`] unless another
+/// [`Transport`] is given to [`Self::new`]. A fake [`Transport`] returning canned responses
+/// makes it possible to test code that uses [`ClientWrapper`] without a network connection.
+/// When the `gzip` or `deflate` feature is enabled, requests ask for a compressed response,
+/// which other [`Transport`]s must decompress before returning it. Requests are sent with
+/// [`DEFAULT_USER_AGENT`] as their `User-Agent`, unless another is set with
+/// [`Self::set_user_agent`], or [`Self::clear_user_agent`] is called to keep the
+/// [`reqwest::Client`]'s own user agent.
+pub struct ClientWrapper<'a, T = Client> {
+    client: &'a T,
+    api_key: &'a str,
+    user_hash: Option<SavedUserHash>,
+    relogin: futures::lock::Mutex<()>,
+    credentials: Option<Credentials>,
+    endpoint: Url,
+    retry_policy: Option<RetryPolicy>,
+    daily_limit: Option<usize>,
+    enforce_daily_limit: bool,
+    usage: Mutex<KeyUsage>,
+    timeout: Option<Duration>,
+    key_cache_ttl: Option<Duration>,
+    key_checked: Mutex<Option<Instant>>,
+    user_agent: Option<reqwest::header::HeaderValue>,
+}
+
+/// Builds a [`ClientWrapper`] with several options at once. Created by
+/// [`ClientWrapper::builder`].
+///
+/// Every option can also be changed later using the corresponding `set_` function on
+/// [`ClientWrapper`].
+pub struct ClientWrapperBuilder<'a, T = Client> {
+    client: &'a T,
     api_key: &'a str,
-    user_hash: Option<String>
+    endpoint: Url,
+    user_hash: Option<String>,
+    credentials: Option<Credentials>,
+    retry_policy: Option<RetryPolicy>,
+    daily_limit: Option<(usize, bool)>,
+    timeout: Option<Duration>,
+    key_cache_ttl: Option<Duration>,
+    user_agent: Option<String>,
+}
+
+/// A `User-Agent` header identifying this crate and its version. [`ClientWrapper`] sends it
+/// with every request unless told otherwise; see [`ClientWrapper::set_user_agent`].
+pub const DEFAULT_USER_AGENT: &str = concat!("brickset-rs/", env!("CARGO_PKG_VERSION"));
+
+/// The username and password used by [`ClientWrapper::set_auto_relogin`].
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+/// The saved user hash token. Automatic re-login replaces the token through `&self`, so
+/// it is kept behind a lock and handed out as an [`Arc`].
+struct SavedUserHash {
+    hash: RwLock<Arc<str>>,
+}
+
+impl SavedUserHash {
+    fn new(hash: String) -> Self {
+        Self { hash: RwLock::new(hash.into()) }
+    }
+
+    fn current(&self) -> Arc<str> {
+        self.hash.read().unwrap().clone()
+    }
+
+    fn replace(&self, hash: String) -> Arc<str> {
+        let hash: Arc<str> = hash.into();
+        *self.hash.write().unwrap() = hash.clone();
+        hash
+    }
+}
+
+/// Controls how [`ClientWrapper`] retries requests that fail with a 5xx status code, or
+/// a connection error or timeout.
+///
+/// Requests that alter the user's collection (see [`BricksetRequest::is_idempotent`]) are
+/// never retried. Each retry counts against the daily limit; see
+/// [`ClientWrapper::set_daily_limit`].
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Maximum number of times a request is retried after the first attempt fails.
+    pub max_retries: u32,
+    /// Delay before the first retry. The delay doubles after each retry.
+    pub base_delay: Duration,
+}
+
+/// Sends HTTP requests on behalf of a [`ClientWrapper`].
+///
+/// [`Transport`] is implemented for [`reqwest::Client`]. Other implementations can be used
+/// to record requests, or to return canned responses in tests.
+pub trait Transport {
+    /// Send `request`, and return the response's status code and body.
+    ///
+    /// Connection errors should be returned as [`Error::Reqwest`], and timeouts as
+    /// [`Error::Timeout`], so that [`ClientWrapper`] knows to retry them. Converting a
+    /// [`reqwest::Error`] into [`Error`] with `?` takes care of this.
+    fn execute(&self, request: RequestData) -> impl Future<Output = Result<ResponseData>> + Send;
+
+    /// Wait for `duration` before retrying a request. Defaults to [`tokio::time::sleep`].
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// An HTTP request built by [`ClientWrapper`], for a [`Transport`] to send.
+///
+/// BrickSet API requests are built by the same code as [`BricksetRequest::to_reqwest`], so
+/// they have the same headers, plus the [`ClientWrapper`]'s timeout and user agent.
+/// Converting a [`RequestData`] into a [`reqwest::Request`] keeps all of them.
+#[derive(Debug, Clone)]
+pub struct RequestData {
+    pub method: reqwest::Method,
+    pub url: Url,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: Vec<u8>,
+    /// See [`ClientWrapper::set_timeout`].
+    pub timeout: Option<Duration>,
+}
+
+/// The parts of an HTTP response used by [`ClientWrapper`].
+#[derive(Debug, Clone)]
+pub struct ResponseData {
+    pub status: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    /// The response body. JSON is parsed straight from the bytes, without copying them into
+    /// a `String` first.
+    pub body: Vec<u8>,
+}
+
+impl RequestData {
+    /// A POST request for `request`, with its parameters form-encoded in the body.
+    pub(crate) fn form<E: BricksetRequest + ?Sized>(request: &E, endpoint: &Url) -> Result<Self, request::Error> {
+        Self::form_with_defaults(request, endpoint, &[])
+    }
+
+    /// Same as [`Self::form`], but each parameter named in `defaults` that `request` left
+    /// empty is set to the given value instead.
+    fn form_with_defaults<E: BricksetRequest + ?Sized>(request: &E, endpoint: &Url, defaults: &[(&str, &str)]) -> Result<Self, request::Error> {
+        let mut body = url::form_urlencoded::Serializer::new(String::new());
+        request.encode_query(&mut body)?;
+        let mut body = body.finish();
+
+        if !defaults.is_empty() {
+            let mut filled = url::form_urlencoded::Serializer::new(String::new());
+            for (name, value) in url::form_urlencoded::parse(body.as_bytes()) {
+                let default = defaults.iter()
+                    .find(|(default_name, _)| *default_name == name)
+                    .map(|(_, default)| *default);
+                match default {
+                    Some(default) if value.is_empty() => filled.append_pair(&name, default),
+                    _ => filled.append_pair(&name, &value),
+                };
+            }
+            body = filled.finish();
+        }
+
+        Self::post(request, endpoint, "application/x-www-form-urlencoded", body)
+    }
+
+    /// A POST request for `request`, with its parameters in a JSON object in the body. See
+    /// [`BricksetRequest::to_reqwest_json`].
+    pub(crate) fn json<E: BricksetRequest + ?Sized>(request: &E, endpoint: &Url) -> Result<Self, request::Error> {
+        let body = serde_json::to_string(&request.encode_json()?)?;
+        Self::post(request, endpoint, "application/json", body)
+    }
+
+    fn post<E: BricksetRequest + ?Sized>(request: &E, endpoint: &Url, content_type: &'static str, body: String) -> Result<Self, request::Error> {
+        use reqwest::header::{self, HeaderValue};
+
+        let url = endpoint.join(request.method_name())?;
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+        headers.insert(header::CONTENT_LENGTH, body.len().into());
+        #[cfg(any(feature = "gzip", feature = "deflate"))]
+        headers.insert(header::ACCEPT_ENCODING, HeaderValue::from_static(request::ACCEPT_ENCODING));
+
+        Ok(RequestData { method: reqwest::Method::POST, url, headers, body: body.into_bytes(), timeout: None })
+    }
+
+    /// A GET request for a file the BrickSet API links to.
+    fn get(url: Url) -> Self {
+        RequestData { method: reqwest::Method::GET, url, headers: Default::default(), body: Vec::new(), timeout: None }
+    }
+
+    /// Build a [`reqwest::Request`] using `client`'s request builder.
+    pub(crate) fn build(self, client: &Client) -> reqwest::Result<reqwest::Request> {
+        let mut builder = client.request(self.method, self.url).headers(self.headers).body(self.body);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build()
+    }
+
+    /// Same as [`Self::build`], but builds a [`reqwest::blocking::Request`].
+    #[cfg(feature = "blocking")]
+    pub(crate) fn build_blocking(self, client: &reqwest::blocking::Client) -> reqwest::Result<reqwest::blocking::Request> {
+        let mut builder = client.request(self.method, self.url).headers(self.headers).body(self.body);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        builder.build()
+    }
+}
+
+impl From<RequestData> for reqwest::Request {
+    fn from(data: RequestData) -> Self {
+        let mut request = reqwest::Request::new(data.method, data.url);
+        *request.headers_mut() = data.headers;
+        if !data.body.is_empty() {
+            *request.body_mut() = Some(data.body.into());
+        }
+        *request.timeout_mut() = data.timeout;
+        request
+    }
+}
+
+impl Transport for Client {
+    async fn execute(&self, request: RequestData) -> Result<ResponseData> {
+        let response = Client::execute(self, request.into()).await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = if status.is_success() {
+            response.bytes().await?.into()
+        } else {
+            response.bytes().await.map(Vec::from).unwrap_or_default()
+        };
+        Ok(ResponseData { status, headers, body })
+    }
+}
+
+/// The result of [`ClientWrapper::download_instructions_conditional`].
+#[derive(Debug, Clone)]
+pub enum Download {
+    /// The file has changed, or neither an ETag nor a modification date was given.
+    Modified {
+        bytes: bytes::Bytes,
+        /// The file's new ETag, if the server sent one.
+        etag: Option<String>,
+        /// The file's `Last-Modified` date, if the server sent one.
+        last_modified: Option<String>,
+    },
+    /// The file hasn't changed since it was downloaded with the given ETag or modification
+    /// date.
+    NotModified,
 }
 
 /// Errors that can be returned by [`ClientWrapper`] API calls.
@@ -61,27 +320,183 @@ pub enum Error {
     Request(request::Error),
     Response(response::Error),
     Json(serde_json::Error),
+    /// Failed to decode the response to a BrickSet API request.
+    Decode {
+        /// The request's method name, e.g. `"getSets"`.
+        method: &'static str,
+        source: serde_json::Error,
+        /// The beginning of the response body, up to 500 bytes long. Invalid UTF-8 is replaced
+        /// with `U+FFFD`.
+        body: String,
+    },
+    /// The server responded with a non-success status code.
     Http {
-        response: reqwest::Response
+        status: reqwest::StatusCode,
+        body: String
     },
     /// Tried to call a [`ClientWrapper`] function that requires a logged-in user,
     /// but the client is not logged in.
-    NotLoggedIn
+    NotLoggedIn,
+    /// The request was not sent, because it would exceed the daily limit set with
+    /// [`ClientWrapper::set_daily_limit`].
+    QuotaExceeded,
+    /// The request timed out. See [`ClientWrapper::set_timeout`].
+    Timeout,
+    /// Failed to read or write a file, e.g. a recorded response.
+    Io(std::io::Error),
 }
 
-impl<'a> ClientWrapper<'a> {
-    /// Create a new [`ClientWrapper`] that will use the given [`reqwest::Client`] and API key.
-    pub fn new(api_key: &'a str, client: &'a Client) -> ClientWrapper<'a> {
-        ClientWrapper { client, api_key, user_hash: None }
+impl<'a, T: Transport> ClientWrapper<'a, T> {
+    /// Create a new [`ClientWrapper`] that will use the given [`reqwest::Client`] (or other
+    /// [`Transport`]) and API key. To set other options up front, use [`Self::builder`].
+    pub fn new(api_key: &'a str, client: &'a T) -> ClientWrapper<'a, T> {
+        Self::with_endpoint(api_key, client, request::ENDPOINT.clone())
+    }
+
+    /// Start building a [`ClientWrapper`] that will use the given [`reqwest::Client`] (or
+    /// other [`Transport`]) and API key.
+    pub fn builder(api_key: &'a str, client: &'a T) -> ClientWrapperBuilder<'a, T> {
+        ClientWrapperBuilder {
+            client,
+            api_key,
+            endpoint: request::ENDPOINT.clone(),
+            user_hash: None,
+            credentials: None,
+            retry_policy: None,
+            daily_limit: None,
+            timeout: None,
+            key_cache_ttl: None,
+            user_agent: Some(DEFAULT_USER_AGENT.to_string()),
+        }
+    }
+
+    /// Same as [`Self::new`], but sends requests to `endpoint` instead of [`request::ENDPOINT`].
+    /// This is useful for testing against a mock server, or for using a mirror of the API.
+    ///
+    /// `endpoint` should end with a trailing slash, e.g. `http://localhost:8080/api/v3.asmx/`.
+    pub fn with_endpoint(api_key: &'a str, client: &'a T, endpoint: Url) -> ClientWrapper<'a, T> {
+        ClientWrapper {
+            client,
+            api_key,
+            user_hash: None,
+            relogin: futures::lock::Mutex::new(()),
+            credentials: None,
+            endpoint,
+            retry_policy: None,
+            daily_limit: None,
+            enforce_daily_limit: false,
+            usage: Mutex::new(KeyUsage::default()),
+            timeout: None,
+            key_cache_ttl: None,
+            key_checked: Mutex::new(None),
+            user_agent: Some(reqwest::header::HeaderValue::from_static(DEFAULT_USER_AGENT)),
+        }
+    }
+
+    /// Retry failed requests according to `retry_policy`. By default, requests are
+    /// not retried.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = Some(retry_policy);
+    }
+
+    /// Stop retrying failed requests.
+    pub fn clear_retry_policy(&mut self) {
+        self.retry_policy = None;
+    }
+
+    /// Fail requests with [`Error::Timeout`] if they take longer than `timeout`. This
+    /// overrides the timeout of the [`reqwest::Client`]. Each retry gets its own timeout.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Stop applying a timeout to each request. The [`reqwest::Client`]'s timeout, if any,
+    /// still applies.
+    pub fn clear_timeout(&mut self) {
+        self.timeout = None;
+    }
+
+    /// Send `user_agent` as the `User-Agent` header of every request, instead of
+    /// [`DEFAULT_USER_AGENT`]. This replaces the [`reqwest::Client`]'s user agent.
+    /// Returns an error if `user_agent` isn't a valid header value.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        let user_agent = reqwest::header::HeaderValue::from_str(user_agent)
+            .map_err(|_| request::Error::Message(format!("Invalid user agent {user_agent:?}")))?;
+        self.user_agent = Some(user_agent);
+        Ok(())
+    }
+
+    /// Stop setting the `User-Agent` header, so requests use the [`reqwest::Client`]'s own
+    /// user agent, if it has one, instead of [`DEFAULT_USER_AGENT`].
+    pub fn clear_user_agent(&mut self) {
+        self.user_agent = None;
+    }
+
+    /// Remember successful [`Self::check_key`] calls for `ttl`. During that time,
+    /// [`Self::check_key`] succeeds without sending a request. By default, every call sends
+    /// a request.
+    pub fn set_key_cache_ttl(&mut self, ttl: Duration) {
+        self.key_cache_ttl = Some(ttl);
+    }
+
+    /// Stop remembering successful [`Self::check_key`] calls.
+    pub fn clear_key_cache_ttl(&mut self) {
+        self.key_cache_ttl = None;
+    }
+
+    /// Forget the last successful [`Self::check_key`] call, so the next call sends a request.
+    pub fn invalidate_key_cache(&mut self) {
+        *self.key_checked.get_mut().unwrap() = None;
+    }
+
+    /// Set the number of requests the API key is allowed to make per day. If `enforce` is true,
+    /// requests that would exceed the limit fail with [`Error::QuotaExceeded`] without being sent.
+    ///
+    /// The [`ClientWrapper`] counts the requests it sends each day (UTC). Requests made by other
+    /// programs using the same API key aren't counted until [`Self::get_key_usage_stats`] is
+    /// called, which replaces the local count with today's usage reported by BrickSet.
+    ///
+    /// Each retry (see [`Self::set_retry_policy`]) is counted as a separate request, because
+    /// BrickSet counts every request it receives. If an enforced limit is reached while
+    /// retrying, the request fails with [`Error::QuotaExceeded`] instead of being retried.
+    pub fn set_daily_limit(&mut self, limit: usize, enforce: bool) {
+        self.daily_limit = Some(limit);
+        self.enforce_daily_limit = enforce;
+    }
+
+    /// Stop tracking the daily request limit.
+    pub fn clear_daily_limit(&mut self) {
+        self.daily_limit = None;
+        self.enforce_daily_limit = false;
+    }
+
+    /// The number of requests remaining today, or `None` if no daily limit has been set
+    /// using [`Self::set_daily_limit`].
+    pub fn remaining_quota(&self) -> Option<usize> {
+        let limit = self.daily_limit?;
+        let used = self.usage.lock().unwrap().today();
+        Some(limit.saturating_sub(used))
     }
 
     /// Check if the [`ClientWrapper`]'s API key is valid.
     /// 
-    /// This function can be used even when the [`ClientWrapper`] is not logged in.
+    /// This function can be used even when the [`ClientWrapper`] is not logged in. If a
+    /// TTL was set with [`Self::set_key_cache_ttl`], a recent successful check is reused
+    /// instead of sending a new request.
     pub async fn check_key(&self) -> Result<response::CheckKeyResponse> {
+        if let Some(ttl) = self.key_cache_ttl {
+            let checked = *self.key_checked.lock().unwrap();
+            if checked.is_some_and(|checked| checked.elapsed() < ttl) {
+                return Ok(response::CheckKeyResponse {});
+            }
+        }
+
         let request = request::CheckKey::new(self.api_key);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        let result = unwrap_response(response)?;
+
+        *self.key_checked.lock().unwrap() = Some(Instant::now());
+        Ok(result)
     }
     
     /// Get information about API key usage.
@@ -90,7 +505,12 @@ impl<'a> ClientWrapper<'a> {
     pub async fn get_key_usage_stats(&self) -> Result<response::GetKeyUsageStatsResponse> {
         let request = request::GetKeyUsageStats::new(self.api_key);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        let result: response::GetKeyUsageStatsResponse = unwrap_response(response)?;
+
+        let today = Utc::now().date_naive();
+        *self.usage.lock().unwrap() = KeyUsage { date: Some(today), count: result.on(today) };
+
+        Ok(result)
     }
 
     /// Log into Brickset with the given username and password. The resulting user hash token
@@ -98,9 +518,9 @@ impl<'a> ClientWrapper<'a> {
     pub async fn log_in(&mut self, username: &str, password: &str) -> Result<response::LoginResponse> {
         let request = request::Login::new(self.api_key, username, password);
         let response = self.execute(request).await?;
-        let result: response::LoginResponse = RespResult::from(response)?;
+        let result: response::LoginResponse = unwrap_response(response)?;
 
-        self.user_hash = Some(result.hash.clone());
+        self.user_hash = Some(SavedUserHash::new(result.hash.clone()));
         Ok(result)
     }
 
@@ -112,11 +532,27 @@ impl<'a> ClientWrapper<'a> {
         Ok(result)
     }
 
+    /// Log in using `user_hash` if one is given and it is still valid, otherwise log in
+    /// using `username` and `password`. Returns the user hash token that is now in use,
+    /// which may be worth caching for next time.
+    pub async fn log_in_or_reuse(&mut self, user_hash: Option<&str>, username: &str, password: &str) -> Result<response::LoginResponse> {
+        if let Some(user_hash) = user_hash {
+            match self.reuse_login(user_hash).await {
+                Ok(_) => return Ok(response::LoginResponse { hash: user_hash.to_string() }),
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    debug!("Could not reuse user hash, logging in with password: {_err}");
+                },
+            }
+        }
+        self.log_in(username, password).await
+    }
+
     /// Log in by reusing an existing user hash token. Be careful! This function
     /// will succeed even if the token is invalid. Consider using [`Self::reuse_login`]
     /// instead.
     pub fn force_reuse_login(&mut self, user_hash: &str) {
-        self.user_hash = Some(user_hash.to_string())
+        self.user_hash = Some(SavedUserHash::new(user_hash.to_string()))
     }
 
     /// Validate a user hash token.
@@ -125,7 +561,7 @@ impl<'a> ClientWrapper<'a> {
     pub async fn check_user_hash(&self, user_hash: &str) -> Result<response::CheckUserHashResponse> {
         let request = request::CheckUserHash::new(self.api_key, user_hash);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Same as [`Self::check_user_hash`], but validates the user token currently being used
@@ -133,8 +569,8 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn validate_login(&self) -> Result<response::CheckUserHashResponse> {
-        match &self.user_hash {
-            Some(user_hash) => self.check_user_hash(user_hash).await,
+        match self.user_hash() {
+            Some(user_hash) => self.check_user_hash(&user_hash).await,
             None => Err(Error::NotLoggedIn)
         }
     }
@@ -145,21 +581,191 @@ impl<'a> ClientWrapper<'a> {
         self.user_hash = None;
     }
 
+    /// Validate the saved user hash token using [`Self::validate_login`], then forget it.
+    /// If the token is invalid, the error is returned and the token is kept.
+    ///
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub async fn try_log_out(&mut self) -> Result<()> {
+        self.validate_login().await?;
+        self.log_out();
+        Ok(())
+    }
+
     /// Returns true if the [`ClientWrapper`] is currently logged in
     #[inline]
     pub fn is_logged_in(&self) -> bool {
         self.user_hash.is_some()
     }
 
+    /// The saved user hash token, if the [`ClientWrapper`] is logged in.
+    ///
+    /// If automatic re-login (see [`Self::set_auto_relogin`]) replaces the token, the
+    /// returned token is not updated. Call this function again to get the new token.
+    #[inline]
+    pub fn user_hash(&self) -> Option<Arc<str>> {
+        self.user_hash.as_ref().map(SavedUserHash::current)
+    }
+
+    /// Automatically log in again with `username` and `password` when BrickSet rejects the
+    /// saved user hash token (see [`Error::is_auth_error`]), then retry the request once.
+    /// This only applies to functions that use the saved token, not the `_as` functions.
+    /// By default, an expired token is reported as an error.
+    ///
+    /// NOTE: the username and password are kept in memory, in plain text, until
+    /// [`Self::clear_auto_relogin`] is called or the [`ClientWrapper`] is dropped. Only
+    /// enable this in processes that you would trust with the user's password anyway.
+    pub fn set_auto_relogin(&mut self, username: &str, password: &str) {
+        self.credentials = Some(Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+    }
+
+    /// Stop logging in again automatically, and forget the username and password given to
+    /// [`Self::set_auto_relogin`].
+    pub fn clear_auto_relogin(&mut self) {
+        self.credentials = None;
+    }
+
+    /// Call `f` with the saved user hash token. If the token has expired and automatic
+    /// re-login is enabled, log in again and call `f` with the new token.
+    async fn with_user_hash<R, F, Fut>(&self, f: F) -> Result<R>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<R>>,
+    {
+        let user_hash = self.user_hash().ok_or(Error::NotLoggedIn)?;
+        match (f(user_hash.to_string()).await, &self.credentials) {
+            (Err(err), Some(credentials)) if err.is_auth_error() => {
+                let user_hash = self.relog_in(credentials, &user_hash).await?;
+                f(user_hash.to_string()).await
+            },
+            (result, _) => result,
+        }
+    }
+
+    /// Replace the expired token `expired` with a new one. Only one request logs in at a
+    /// time; if another request has already replaced the token, the replacement is returned
+    /// without logging in again.
+    async fn relog_in(&self, credentials: &Credentials, expired: &str) -> Result<Arc<str>> {
+        let _relogin = self.relogin.lock().await;
+        let saved = self.user_hash.as_ref().ok_or(Error::NotLoggedIn)?;
+        let current = saved.current();
+        if &*current != expired {
+            return Ok(current);
+        }
+
+        #[cfg(feature = "log")]
+        debug!("User hash token was rejected, logging in again");
+        let request = request::Login::new(self.api_key, &credentials.username, &credentials.password);
+        let response = self.execute(request).await?;
+        let result: response::LoginResponse = unwrap_response(response)?;
+
+        Ok(saved.replace(result.hash))
+    }
+
     /// Retrieve a paginated list of sets, or more information about a particular set. You may
     /// find these functions convenient for some use cases:
     /// 
     /// - [`Self::get_wanted_sets`]
     /// - [`Self::get_owned_sets`]
+    ///
+    /// If `params` filters by the user's owned or wanted sets, and the [`ClientWrapper`]
+    /// is not logged in, this function will return an error.
     pub async fn get_sets<'s>(&self, params: request::GetSetsParameters<'s>) -> Result<response::GetSetsResponse> {
-        let request = request::GetSets::new(self.api_key, self.user_hash.as_deref(), params);
+        if self.is_logged_in() {
+            return self.with_user_hash(|user_hash| {
+                let params = params.clone();
+                async move { self.get_sets_as(&user_hash, params).await }
+            }).await;
+        }
+        if params.requires_user_hash() {
+            return Err(Error::NotLoggedIn);
+        }
+        self.get_sets_as("", params).await
+    }
+
+    /// Same as [`Self::get_sets`], but uses `user_hash` instead of the [`ClientWrapper`]'s
+    /// saved user hash token. This function can be used even when the [`ClientWrapper`] is
+    /// not logged in. An empty `user_hash` sends the request without a user.
+    pub async fn get_sets_as<'s>(&self, user_hash: &str, params: request::GetSetsParameters<'s>) -> Result<response::GetSetsResponse> {
+        params.validate()?;
+        let request = request::GetSets::new(self.api_key, Some(user_hash), params);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
+    }
+
+    /// Retrieve every set matching `params`, one page at a time. Each page is fetched with
+    /// the page size given in `params` (default = 500), and the sets are yielded one by one
+    /// until all `matches` have been retrieved.
+    ///
+    /// The page number in `params` is ignored. If a request fails, the error is yielded and
+    /// the stream ends.
+    pub fn get_sets_stream<'s>(&'s self, params: request::GetSetsParameters<'s>) -> impl Stream<Item = Result<response::Set>> + 's {
+        let page_size = params.get_page_size().unwrap_or(500);
+
+        let state = SetsStreamState {
+            params,
+            page_size,
+            page_number: 1,
+            fetched: 0,
+            buffer: Vec::new().into_iter(),
+            done: false,
+        };
+
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                if let Some(set) = state.buffer.next() {
+                    return Some((Ok(set), state));
+                }
+                if state.done {
+                    return None;
+                }
+
+                let params = state.params.clone()
+                    .page_size(state.page_size)
+                    .page_number(state.page_number);
+
+                match self.get_sets(params).await {
+                    Ok(response) => {
+                        state.page_number += 1;
+                        state.fetched += response.sets.len();
+                        state.done = response.sets.is_empty() || state.fetched >= response.matches;
+                        state.buffer = response.sets.into_iter();
+                    }
+                    Err(err) => {
+                        state.done = true;
+                        return Some((Err(err), state));
+                    }
+                }
+            }
+        })
+    }
+
+    /// Retrieve every set matching `params`. The first page is fetched to learn how many sets
+    /// match, then the remaining pages are fetched concurrently, with at most
+    /// `max_concurrency` requests in flight at once. Each page is fetched with the page
+    /// size given in `params` (default = 500), and the page number in `params` is ignored.
+    ///
+    /// The sets are returned in the same order as [`Self::get_sets_stream`] would yield them.
+    /// If any request fails, the remaining requests are cancelled and the error is returned.
+    pub async fn get_all_sets<'s>(&self, params: request::GetSetsParameters<'s>, max_concurrency: usize) -> Result<Vec<response::Set>> {
+        let page_size = params.get_page_size().unwrap_or(500);
+
+        let first = self.get_sets(params.clone().page_size(page_size).page_number(1)).await?;
+        let page_count = first.page_count(page_size);
+
+        let pages: Vec<_> = stream::iter(2..=page_count)
+            .map(|page_number| self.get_sets(params.clone().page_size(page_size).page_number(page_number)))
+            .buffered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        let mut sets = first.sets;
+        for page in pages {
+            sets.extend(page.sets);
+        }
+        Ok(sets)
     }
 
     /// Get the user's wanted sets. For additional filtering options, use [`Self::get_sets`].
@@ -185,6 +791,9 @@ impl<'a> ClientWrapper<'a> {
     }
 
     /// Get the user's owned sets. For additional filtering options, use [`Self::get_sets`].
+    ///
+    /// Version 3 of the BrickSet API has no method for collection totals. The response's
+    /// `matches` is the number of different sets the user owns, even with a small page size.
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn get_owned_sets(&self, order_by: Option<request::OrderBy>, page_size: Option<usize>, page_number: Option<usize>, extended_data: bool) -> Result<response::GetSetsResponse> {
@@ -206,46 +815,219 @@ impl<'a> ClientWrapper<'a> {
         self.get_sets(params).await
     }
 
+    /// Get sets that were updated on or after the day of `since` (UTC). Useful for
+    /// incremental syncs. This doesn't require a logged-in user; to only get the user's
+    /// owned or wanted sets, use [`Self::get_sets`] with
+    /// [`request::GetSetsParameters::updated_since_datetime`].
+    pub async fn get_sets_updated_since(&self, since: DateTime<Utc>, page_size: Option<usize>) -> Result<response::GetSetsResponse> {
+        let mut params = request::GetSetsParameters::new()
+            .updated_since_datetime(since);
+        if let Some(page_size) = page_size {
+            params = params.page_size(page_size);
+        }
+        self.get_sets(params).await
+    }
+
+    /// Get additional images for a particular set.
+    pub async fn get_additional_images(&self, set_id: u64) -> Result<response::GetAdditionalImagesResponse> {
+        let request = request::GetAdditionalImages::new(self.api_key, set_id);
+        let response = self.execute(request).await?;
+        unwrap_response(response)
+    }
+
     /// Get instructions for a particular set.
     pub async fn get_instructions(&self, set_id: u64) -> Result<response::GetInstructionsResponse> {
         let request = request::GetInstructions::new(self.api_key, set_id);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Get instructions for a particular set.
     pub async fn get_instructions_2(&self, set_number: &str) -> Result<response::GetInstructionsResponse> {
         let request = request::GetInstructions2::new(self.api_key, set_number);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
+    }
+
+    /// Get a single set by its ID, or `None` if there is no such set. If `extended` is true,
+    /// extended information like tags and the description is included; see
+    /// [`request::GetSetsParameters::extended_data`].
+    pub async fn get_set(&self, set_id: u64, extended: bool) -> Result<Option<response::Set>> {
+        let params = request::GetSetsParameters::new()
+            .set_id(set_id)
+            .extended_data(extended);
+        let response = self.get_sets(params).await?;
+        Ok(response.sets.into_iter().next())
+    }
+
+    /// Get a single set by its set number, or `None` if there is no matching set. If `number`
+    /// is a [`request::SetNumber`] with a variant number (e.g. `"6876-1"`), only that exact
+    /// set is returned. Otherwise, `number` is used as a query, and the set whose number
+    /// matches it exactly is returned, preferring the lowest variant. If no set number
+    /// matches exactly, the first result is returned.
+    pub async fn get_set_by_number(&self, number: &str, extended: bool) -> Result<Option<response::Set>> {
+        let number = number.trim();
+        if let Ok(request::SetNumber { number, variant: Some(variant) }) = number.parse() {
+            let params = request::GetSetsParameters::new()
+                .full_set_number(format!("{number}-{variant}"))
+                .extended_data(extended);
+            let response = self.get_sets(params).await?;
+            return Ok(response.sets.into_iter().find(|set| {
+                set.number.eq_ignore_ascii_case(&number) && set.number_variant == variant as usize
+            }));
+        }
+
+        let params = request::GetSetsParameters::new().query(number);
+        let response = self.get_sets(params.extended_data(extended)).await?;
+
+        let mut sets = response.sets;
+        let exact = sets.iter()
+            .enumerate()
+            .filter(|(_, set)| set.number.eq_ignore_ascii_case(number))
+            .min_by_key(|(_, set)| set.number_variant)
+            .map(|(i, _)| i);
+
+        Ok(match exact {
+            Some(i) => Some(sets.swap_remove(i)),
+            None => sets.into_iter().next(),
+        })
+    }
+
+    /// Look up the set ID of a set, given its full set number including the variant number,
+    /// e.g. `"6876-1"`. Returns `None` if there is no matching set.
+    ///
+    /// This is useful for calling functions like [`Self::get_instructions`] and
+    /// [`Self::get_reviews`], which take a set ID instead of a set number.
+    pub async fn resolve_set_id(&self, set_number: &str) -> Result<Option<u64>> {
+        let params = request::GetSetsParameters::new()
+            .full_set_number(set_number);
+        let response = self.get_sets(params).await?;
+        Ok(response.sets.first().map(|set| set.set_id))
     }
 
     /// Get reviews for a particular set.
+    ///
+    /// The BrickSet API can't page through reviews, so every review of the set is returned
+    /// at once. Popular sets can have many reviews; see [`Self::get_reviews_limited`].
+    ///
+    /// Reviews are read-only: version 3 of the BrickSet API has no method for publishing
+    /// a review, so reviews can only be written on the BrickSet website.
     pub async fn get_reviews(&self, set_id: u64) -> Result<response::GetReviewsResponse> {
         let request = request::GetReviews::new(self.api_key, set_id);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
+    }
+
+    /// Same as [`Self::get_reviews`], but keeps at most `max` reviews. BrickSet still sends
+    /// every review, so this is done client-side, and doesn't make the request any smaller.
+    /// `matches` remains the total number of reviews.
+    pub async fn get_reviews_limited(&self, set_id: u64, max: usize) -> Result<response::GetReviewsResponse> {
+        let mut response = self.get_reviews(set_id).await?;
+        response.reviews.truncate(max);
+        Ok(response)
+    }
+
+    /// Download the file that `instructions` points to, usually a PDF.
+    ///
+    /// The file is hosted by LEGO or BrickSet's CDN, not the BrickSet API, so downloading it
+    /// doesn't count against the daily limit, and is never retried. The timeout still
+    /// applies, which may need to be raised for large files.
+    pub async fn download_instructions(&self, instructions: &response::Instructions) -> Result<bytes::Bytes> {
+        let url = Url::parse(&instructions.url).map_err(request::Error::from)?;
+        let mut http_request = RequestData::get(url);
+        self.configure(&mut http_request);
+
+        let ResponseData { status, body, .. } = self.client.execute(http_request).await?;
+        if !status.is_success() {
+            return Err(Error::Http { status, body: String::from_utf8_lossy(&body).into_owned() })
+        }
+        Ok(body.into())
+    }
+
+    /// Same as [`Self::download_instructions`], but takes the file's URL, and skips the
+    /// download if the file hasn't changed since it was downloaded with the ETag `etag`, or
+    /// since the `Last-Modified` date `last_modified`. This works for any file the BrickSet
+    /// API links to, including [`response::Image`]s.
+    ///
+    /// Keep the ETag and modification date from [`Download::Modified`] alongside the file,
+    /// and pass them here the next time the file is needed. Servers that don't send ETags
+    /// often send a modification date instead. If both are given, the server decides which
+    /// one to use; HTTP says the ETag wins.
+    pub async fn download_instructions_conditional(&self, url: &str, etag: Option<&str>, last_modified: Option<&str>) -> Result<Download> {
+        let url = Url::parse(url).map_err(request::Error::from)?;
+        let mut http_request = RequestData::get(url);
+        self.configure(&mut http_request);
+        if let Some(etag) = etag {
+            let etag = reqwest::header::HeaderValue::from_str(etag)
+                .map_err(|_| request::Error::Message(format!("Invalid ETag {etag:?}")))?;
+            http_request.headers.insert(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = last_modified {
+            let last_modified = reqwest::header::HeaderValue::from_str(last_modified)
+                .map_err(|_| request::Error::Message(format!("Invalid modification date {last_modified:?}")))?;
+            http_request.headers.insert(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+
+        let ResponseData { status, headers, body } = self.client.execute(http_request).await?;
+        if status == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(Download::NotModified)
+        }
+        if !status.is_success() {
+            return Err(Error::Http { status, body: String::from_utf8_lossy(&body).into_owned() })
+        }
+
+        let header = |name| headers.get(name)
+            .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+            .map(str::to_string);
+        Ok(Download::Modified {
+            bytes: body.into(),
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        })
     }
 
     /// Get all themes, with the total number of sets in each theme.
     pub async fn get_themes(&self) -> Result<response::GetThemesResponse> {
         let request = request::GetThemes::new(self.api_key);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Get subthemes for the given theme, with the total number of sets in each subtheme.
     pub async fn get_subthemes(&self, theme: &str) -> Result<response::GetSubthemesResponse> {
         let request = request::GetSubthemes::new(self.api_key, theme);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Get a list of years for a given theme, with the total number of sets in each year.
     pub async fn get_years(&self, theme: &str) -> Result<response::GetYearsResponse> {
         let request = request::GetYears::new(self.api_key, theme);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
+    }
+
+    /// Get a theme, its subthemes, and the years it was active. Returns an error if there is
+    /// no theme named `theme` (ignoring ASCII case).
+    ///
+    /// This sends `getThemes`, `getSubthemes` and `getYears` requests concurrently, so it
+    /// counts as three API calls against the daily quota.
+    pub async fn get_theme_tree(&self, theme: &str) -> Result<response::ThemeTree> {
+        let (themes, subthemes, years) = futures::try_join!(
+            self.get_themes(),
+            self.get_subthemes(theme),
+            self.get_years(theme),
+        )?;
+
+        let theme = themes.find(theme)
+            .cloned()
+            .ok_or_else(|| request::Error::Message(format!("No theme named {theme:?}")))?;
+
+        Ok(response::ThemeTree {
+            theme,
+            subthemes: subthemes.subthemes,
+            years: years.years,
+        })
     }
 
     /// Alter the user's collection. You may find these functions more convenient:
@@ -256,12 +1038,44 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn set_collection<'s>(&self, set_id: u64, params: request::SetCollectionParameters<'s>) -> Result<response::SetCollectionResponse> {
-        if !self.is_logged_in() {
-            return Err(Error::NotLoggedIn);
-        }
-        let request = request::SetCollection::new(self.api_key, self.user_hash.as_deref().unwrap(), set_id, params);
+        self.with_user_hash(|user_hash| {
+            let params = params.clone();
+            async move { self.set_collection_as(&user_hash, set_id, params).await }
+        }).await
+    }
+
+    /// Same as [`Self::set_collection`], but alters the collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub async fn set_collection_as<'s>(&self, user_hash: &str, set_id: u64, params: request::SetCollectionParameters<'s>) -> Result<response::SetCollectionResponse> {
+        let request = request::SetCollection::new(self.api_key, user_hash, set_id, params);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
+    }
+
+    /// Alter the user's collection for many sets at once. The BrickSet API can only alter
+    /// one set per request, so this sends one `setCollection` request per item, with at most
+    /// `concurrency` requests in flight at once. Each request counts against the daily quota.
+    ///
+    /// Returns one result per item, in the same order as `items`. A failed request doesn't
+    /// stop the others from being sent. If the [`ClientWrapper`] is not logged in, every
+    /// result will be an error.
+    pub async fn set_collection_many<'s>(&self, items: impl IntoIterator<Item = (u64, request::SetCollectionParameters<'s>)>, concurrency: usize) -> Vec<Result<response::SetCollectionResponse>> {
+        stream::iter(items)
+            .map(|(set_id, params)| self.set_collection(set_id, params))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    /// Same as [`Self::set_collection_many`], but alters the collection of the user identified
+    /// by `user_hash`.
+    pub async fn set_collection_many_as<'s>(&self, user_hash: &str, items: impl IntoIterator<Item = (u64, request::SetCollectionParameters<'s>)>, concurrency: usize) -> Vec<Result<response::SetCollectionResponse>> {
+        stream::iter(items)
+            .map(|(set_id, params)| self.set_collection_as(user_hash, set_id, params))
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
     }
 
     /// Add or remove a set from the user's wanted list.
@@ -308,12 +1122,16 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn get_notes(&self) -> Result<response::GetUserNotesResponse> {
-        if !self.is_logged_in() {
-            return Err(Error::NotLoggedIn);
-        }
-        let request = request::GetUserNotes::new(self.api_key, self.user_hash.as_deref().unwrap());
+        self.with_user_hash(|user_hash| async move { self.get_notes_as(&user_hash).await }).await
+    }
+
+    /// Same as [`Self::get_notes`], but gets the set notes of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub async fn get_notes_as(&self, user_hash: &str) -> Result<response::GetUserNotesResponse> {
+        let request = request::GetUserNotes::new(self.api_key, user_hash);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Get the user's minifig collection. You may find these functions more convenient:
@@ -323,12 +1141,19 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn get_minifig_collection<'s>(&self, params: GetMinifigCollectionParameters<'s>) -> Result<response::GetMinifigCollectionResponse> {
-        if !self.is_logged_in() {
-            return Err(Error::NotLoggedIn);
-        }
-        let request = request::GetMinifigCollection::new(self.api_key, self.user_hash.as_deref().unwrap(), params);
+        self.with_user_hash(|user_hash| {
+            let params = params.clone();
+            async move { self.get_minifig_collection_as(&user_hash, params).await }
+        }).await
+    }
+
+    /// Same as [`Self::get_minifig_collection`], but gets the minifig collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub async fn get_minifig_collection_as<'s>(&self, user_hash: &str, params: GetMinifigCollectionParameters<'s>) -> Result<response::GetMinifigCollectionResponse> {
+        let request = request::GetMinifigCollection::new(self.api_key, user_hash, params);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Get a list of minifigs owned by the user. If not None, `query` is used to filter the
@@ -365,12 +1190,19 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn set_minifig_collection<'s>(&self, minifig_number: &str, params: SetMinifigCollectionParameters<'s>) -> Result<response::SetMinifigCollectionResponse> {
-        if !self.is_logged_in() {
-            return Err(Error::NotLoggedIn);
-        }
-        let request = request::SetMinifigCollection::new(self.api_key, self.user_hash.as_deref().unwrap(), minifig_number, params);
+        self.with_user_hash(|user_hash| {
+            let params = params.clone();
+            async move { self.set_minifig_collection_as(&user_hash, minifig_number, params).await }
+        }).await
+    }
+
+    /// Same as [`Self::set_minifig_collection`], but alters the minifig collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub async fn set_minifig_collection_as<'s>(&self, user_hash: &str, minifig_number: &str, params: SetMinifigCollectionParameters<'s>) -> Result<response::SetMinifigCollectionResponse> {
+        let request = request::SetMinifigCollection::new(self.api_key, user_hash, minifig_number, params);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
     /// Add or remove a minifig from the user's owned list.
@@ -407,39 +1239,396 @@ impl<'a> ClientWrapper<'a> {
     /// 
     /// If the [`ClientWrapper`] is not logged in, this function will return an error.
     pub async fn get_minifig_notes(&self) -> Result<response::GetMinifigUserNotesResponse> {
-        if !self.is_logged_in() {
-            return Err(Error::NotLoggedIn);
-        }
-        let request = request::GetUserMinifigNotes::new(self.api_key, self.user_hash.as_deref().unwrap());
+        self.with_user_hash(|user_hash| async move { self.get_minifig_notes_as(&user_hash).await }).await
+    }
+
+    /// Same as [`Self::get_minifig_notes`], but gets the minifig notes of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub async fn get_minifig_notes_as(&self, user_hash: &str) -> Result<response::GetMinifigUserNotesResponse> {
+        let request = request::GetUserMinifigNotes::new(self.api_key, user_hash);
         let response = self.execute(request).await?;
-        Ok(RespResult::from(response)?)
+        unwrap_response(response)
     }
 
-    async fn execute<E, T>(&self, request: E) -> Result<Response<T>>
+    /// Count a request against the daily limit. Returns false without counting the
+    /// request if it would exceed the limit.
+    fn count_request(&self) -> bool {
+        let mut usage = self.usage.lock().unwrap();
+        let today = Utc::now().date_naive();
+        if usage.date != Some(today) {
+            *usage = KeyUsage { date: Some(today), count: 0 };
+        }
+
+        match self.daily_limit {
+            Some(limit) if self.enforce_daily_limit && usage.count >= limit => false,
+            _ => {
+                usage.count += 1;
+                true
+            }
+        }
+    }
+
+    /// Build the [`reqwest::Request`] that [`Self::execute`] would send for `request`, without
+    /// sending it. The request is sent to the [`ClientWrapper`]'s endpoint, and has its
+    /// timeout and headers. It doesn't count against the daily limit.
+    ///
+    /// If `request` was built with an empty API key or user hash, the [`ClientWrapper`]'s
+    /// API key and saved user hash token are filled in, so e.g.
+    /// `request::GetUserNotes::new("", "")` prepares a request for the logged-in user's
+    /// notes. Non-empty values are kept as they are.
+    pub fn prepare<E: BricksetRequest>(&self, request: E) -> Result<reqwest::Request> {
+        Ok(self.prepare_data(&request)?.into())
+    }
+
+    /// Same as [`Self::prepare`], but returns a [`RequestData`].
+    pub(crate) fn prepare_data<E: BricksetRequest>(&self, request: &E) -> Result<RequestData> {
+        let user_hash = self.user_hash();
+        let defaults = [("apiKey", self.api_key), ("userHash", user_hash.as_deref().unwrap_or_default())];
+        let mut http_request = RequestData::form_with_defaults(request, &self.endpoint, &defaults)?;
+        self.configure(&mut http_request);
+        Ok(http_request)
+    }
+
+    /// Send any [`BricksetRequest`], using the [`ClientWrapper`]'s endpoint, retry policy,
+    /// daily limit and timeout. The response is parsed into a [`Response`]; use
+    /// [`unwrap_response`] to convert it into a [`Result`](std::result::Result).
+    ///
+    /// BrickSet sometimes responds to a request with no results with a bare `[]` instead of
+    /// a JSON object. For methods that return a list, like `getSets`, this is parsed as a
+    /// successful response with no matches.
+    ///
+    /// With the `tracing` feature, each request is wrapped in a `brickset_request` span,
+    /// with the method name, HTTP status code and response size as fields.
+    pub async fn execute<E, R>(&self, request: E) -> Result<Response<R>>
+    where
+        R: serde::de::DeserializeOwned,
+        E: BricksetRequest
+    {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+
+            let span = tracing::debug_span!(
+                "brickset_request",
+                method = request.method_name(),
+                status = tracing::field::Empty,
+                response_size = tracing::field::Empty,
+            );
+            self.execute_inner(request).instrument(span).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        self.execute_inner(request).await
+    }
+
+    async fn execute_inner<E, R>(&self, request: E) -> Result<Response<R>>
     where
-        T: serde::de::DeserializeOwned,
+        R: serde::de::DeserializeOwned,
         E: BricksetRequest
     {
         #[cfg(feature = "log")]
         debug!("Executing Brickset API request: {}", request.method_name());
 
-        let request = request.to_reqwest(&self.client)?;
+        let retry_policy = self.retry_policy.filter(|_| request.is_idempotent());
+        let mut attempt = 0;
+
+        let response = loop {
+            if !self.count_request() {
+                return Err(Error::QuotaExceeded);
+            }
+
+            let http_request = self.build_request(&request)?;
+
+            let result = self.client.execute(http_request).await;
 
-        let response = self.client.execute(request).await?;
+            let retryable = match &result {
+                Ok(response) => response.status.is_server_error(),
+                Err(err) => err.is_retryable(),
+            };
 
-        if !response.status().is_success() {
-            return Err(Error::Http { response })
+            match retry_policy {
+                Some(retry_policy) if retryable && attempt < retry_policy.max_retries => {
+                    #[cfg(feature = "log")]
+                    warn!("Brickset API request {} failed, retrying", request.method_name());
+
+                    self.client.sleep(retry_policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                _ => break result?,
+            }
+        };
+
+        let ResponseData { status, body, .. } = response;
+
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record("status", status.as_u16())
+            .record("response_size", body.len());
+
+        if !status.is_success() {
+            return Err(Error::Http { status, body: String::from_utf8_lossy(&body).into_owned() })
+        }
+
+        parse_body(request.method_name(), &body)
+    }
+
+    /// Build the request [`Self::execute`] sends for `request`.
+    fn build_request<E: BricksetRequest>(&self, request: &E) -> Result<RequestData> {
+        let mut http_request = RequestData::form(request, &self.endpoint)?;
+        self.configure(&mut http_request);
+        Ok(http_request)
+    }
+
+    /// Apply the [`ClientWrapper`]'s timeout to `http_request`, and set its `User-Agent`
+    /// header unless [`Self::clear_user_agent`] was called.
+    fn configure(&self, http_request: &mut RequestData) {
+        http_request.timeout = self.timeout;
+        if let Some(user_agent) = &self.user_agent {
+            http_request.headers.insert(reqwest::header::USER_AGENT, user_agent.clone());
+        }
+    }
+}
+
+/// Convert a [`Response`] into a [`Result`](std::result::Result), mapping an error response to
+/// [`Error::Response`]. This is the conversion used by every [`ClientWrapper`] function,
+/// and can be used with [`ClientWrapper::execute`] to send other requests.
+pub fn unwrap_response<T>(response: Response<T>) -> Result<T> {
+    match response {
+        Response::Ok(ok) => Ok(ok),
+        Response::Err(err) => Err(Error::Response(err)),
+    }
+}
+
+/// Parse the body of a successful HTTP response.
+///
+/// BrickSet sometimes responds to a request with no results with a bare `[]`, instead of an
+/// object like `{"status":"success","matches":0,"sets":[]}`. A bare `[]` is treated as a
+/// successful response with no matches, if `method` returns a list. For other methods, a
+/// bare `[]` is a decode error, like any other unexpected body.
+fn parse_body<R: serde::de::DeserializeOwned>(method: &'static str, body: &[u8]) -> Result<Response<R>> {
+    let is_empty_array = body.trim_ascii()
+        .strip_prefix(b"[")
+        .and_then(|rest| rest.strip_suffix(b"]"))
+        .is_some_and(|inner| inner.trim_ascii().is_empty());
+
+    let result = match list_field(method) {
+        Some(field) if is_empty_array => {
+            serde_json::from_value(serde_json::json!({"status": "success", "matches": 0, field: []}))
+        },
+        _ => serde_json::from_slice(body),
+    };
+    result.map_err(|source| Error::Decode { method, source, body: truncate_body(body) })
+}
+
+/// The name of the list in a successful response to `method`, if it has one.
+fn list_field(method: &str) -> Option<&'static str> {
+    match method {
+        "getKeyUsageStats" => Some("apiKeyUsage"),
+        "getSets" => Some("sets"),
+        "getAdditionalImages" => Some("additionalImages"),
+        "getInstructions" | "getInstructions2" => Some("instructions"),
+        "getReviews" => Some("reviews"),
+        "getThemes" => Some("themes"),
+        "getSubthemes" => Some("subthemes"),
+        "getYears" => Some("years"),
+        "getUserNotes" => Some("userNotes"),
+        "getMinifigCollection" => Some("minifigs"),
+        "getUserMinifigNotes" => Some("userMinifigNotes"),
+        _ => None,
+    }
+}
+
+/// Copy the first 500 bytes of a response body, for [`Error::Decode`].
+fn truncate_body(body: &[u8]) -> String {
+    const MAX_LEN: usize = 500;
+
+    let body = &body[..body.len().min(MAX_LEN)];
+    match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        // The body was cut off in the middle of a character
+        Err(err) if err.error_len().is_none() => String::from_utf8_lossy(&body[..err.valid_up_to()]).into_owned(),
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+/// Number of requests sent on a particular day.
+#[derive(Debug, Default)]
+struct KeyUsage {
+    date: Option<NaiveDate>,
+    count: usize,
+}
+
+impl KeyUsage {
+    fn today(&self) -> usize {
+        if self.date == Some(Utc::now().date_naive()) {
+            self.count
+        } else {
+            0
         }
+    }
+}
 
-        let text = response.text().await?;
+impl RetryPolicy {
+    /// Create a new [`RetryPolicy`].
+    pub fn new(max_retries: u32, base_delay: Duration) -> Self {
+        RetryPolicy { max_retries, base_delay }
+    }
 
-        Ok(serde_json::from_str(&text)?)
+    /// The delay before retry number `attempt`, counting from zero.
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay.saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Retry up to 3 times, starting with a 500 ms delay.
+    fn default() -> Self {
+        RetryPolicy::new(3, Duration::from_millis(500))
+    }
+}
+
+impl<'a, T: Transport> ClientWrapperBuilder<'a, T> {
+    /// Send requests to `endpoint` instead of [`request::ENDPOINT`]. See
+    /// [`ClientWrapper::with_endpoint`].
+    pub fn endpoint(mut self, endpoint: Url) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    /// Start out logged in with an existing user hash token, without checking that it is
+    /// valid. See [`ClientWrapper::force_reuse_login`].
+    pub fn user_hash(mut self, user_hash: &str) -> Self {
+        self.user_hash = Some(user_hash.to_string());
+        self
+    }
+
+    /// See [`ClientWrapper::set_auto_relogin`], including its note about storing the
+    /// password.
+    pub fn auto_relogin(mut self, username: &str, password: &str) -> Self {
+        self.credentials = Some(Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    /// See [`ClientWrapper::set_retry_policy`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// See [`ClientWrapper::set_daily_limit`].
+    pub fn daily_limit(mut self, limit: usize, enforce: bool) -> Self {
+        self.daily_limit = Some((limit, enforce));
+        self
+    }
+
+    /// See [`ClientWrapper::set_timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// See [`ClientWrapper::set_key_cache_ttl`].
+    pub fn key_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.key_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// See [`ClientWrapper::set_user_agent`]. If neither this nor
+    /// [`Self::client_user_agent`] is called, requests are sent with [`DEFAULT_USER_AGENT`].
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Keep the [`reqwest::Client`]'s own user agent. See [`ClientWrapper::clear_user_agent`].
+    pub fn client_user_agent(mut self) -> Self {
+        self.user_agent = None;
+        self
+    }
+
+    /// Build the [`ClientWrapper`]. Returns an error if the options can't work together:
+    /// an endpoint without a trailing slash, a zero timeout, an enforced daily limit of
+    /// zero, automatic re-login with an empty username or password, or an invalid user
+    /// agent.
+    pub fn build(self) -> Result<ClientWrapper<'a, T>> {
+        if self.endpoint.cannot_be_a_base() || !self.endpoint.path().ends_with('/') {
+            return Err(request::Error::Message(format!("Endpoint {} must end with a trailing slash", self.endpoint)).into());
+        }
+        if self.timeout == Some(Duration::ZERO) {
+            return Err(request::Error::Message("Zero timeout is not valid".to_string()).into());
+        }
+        if let Some((0, true)) = self.daily_limit {
+            return Err(request::Error::Message("Enforcing a daily limit of zero would block every request".to_string()).into());
+        }
+        if self.credentials.as_ref().is_some_and(|c| c.username.is_empty() || c.password.is_empty()) {
+            return Err(request::Error::Message("Automatic re-login requires a username and password".to_string()).into());
+        }
+
+        let mut client = ClientWrapper::with_endpoint(self.api_key, self.client, self.endpoint);
+        client.user_hash = self.user_hash.map(SavedUserHash::new);
+        client.credentials = self.credentials;
+        client.retry_policy = self.retry_policy;
+        if let Some((limit, enforce)) = self.daily_limit {
+            client.set_daily_limit(limit, enforce);
+        }
+        client.timeout = self.timeout;
+        client.key_cache_ttl = self.key_cache_ttl;
+        match self.user_agent {
+            Some(user_agent) => client.set_user_agent(&user_agent)?,
+            None => client.clear_user_agent(),
+        }
+        Ok(client)
+    }
+}
+
+struct SetsStreamState<'s> {
+    params: request::GetSetsParameters<'s>,
+    page_size: usize,
+    page_number: usize,
+    fetched: usize,
+    buffer: std::vec::IntoIter<response::Set>,
+    done: bool,
+}
+
+impl Error {
+    /// Returns true if the request might succeed if it is sent again: the server responded
+    /// with a 5xx status code, the connection failed, or the request timed out.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Http { status, .. } => status.is_server_error(),
+            Error::Reqwest(err) => err.is_connect() || err.is_timeout(),
+            Error::Timeout => true,
+            _ => false,
+        }
+    }
+
+    /// Returns true if BrickSet rejected the request's user hash token, meaning the user
+    /// needs to log in again.
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            Error::Response(err) => err.kind() == response::ApiErrorKind::InvalidUserHash,
+            _ => false,
+        }
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(value: reqwest::Error) -> Error {
-        Error::Reqwest(value)
+        if value.is_timeout() {
+            Error::Timeout
+        } else {
+            Error::Reqwest(value)
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Error {
+        Error::Io(value)
     }
 }
 
@@ -469,8 +1658,26 @@ impl std::fmt::Display for Error {
             Error::Request(e) => e.fmt(f),
             Error::Response(e) => e.fmt(f),
             Error::Json(e) => e.fmt(f),
-            Error::Http { response } => write!(f, "HTTP request failed with status code {}", response.status()),
-            Error::NotLoggedIn => write!(f, "Not logged in")
+            Error::Decode { method, source, .. } => write!(f, "failed to decode {method} response: {source}"),
+            Error::Http { status, body } => write!(f, "HTTP request failed with status code {status}: {body}"),
+            Error::NotLoggedIn => write!(f, "Not logged in"),
+            Error::QuotaExceeded => write!(f, "Daily request limit exceeded"),
+            Error::Timeout => write!(f, "Request timed out"),
+            Error::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Reqwest(e) => Some(e),
+            Error::Request(e) => Some(e),
+            Error::Response(e) => Some(e),
+            Error::Json(e) => Some(e),
+            Error::Decode { source, .. } => Some(source),
+            Error::Io(e) => Some(e),
+            _ => None,
         }
     }
 }
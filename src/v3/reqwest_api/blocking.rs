@@ -0,0 +1,677 @@
+//! [`ClientWrapper`] is a blocking version of [`super::ClientWrapper`], built on
+//! [`reqwest::blocking`]. It does not require an async runtime.
+//!
+//! The `blocking` and `reqwest` features can be enabled at the same time. `blocking`
+//! implies `reqwest`, so the async [`super::ClientWrapper`] is always available alongside
+//! this one.
+//!
+//! # Sample
+//!
+//! ```no_run
+//! use brickset::{reqwest_api::blocking::ClientWrapper, request::GetSetsParameters};
+//!
+//! const API_KEY: &str = "<your API key>";
+//!
+//! fn main() {
+//!     let reqwest_client = reqwest::blocking::Client::default();
+//!     let client = ClientWrapper::new(API_KEY, &reqwest_client);
+//!
+//!     let params = GetSetsParameters::new()
+//!         .query("fire truck")
+//!         .theme("City");
+//!
+//!     let sets = client.get_sets(params).expect("get_sets");
+//!
+//!     println!("Found {} matching sets", sets.matches);
+//!     for set in sets.sets.iter() {
+//!         println!("{} {}", set.number, set.name.as_deref().unwrap_or("(Unknown)"));
+//!     }
+//! }
+//! ```
+
+use chrono::{DateTime, Utc};
+use futures::executor::{block_on, block_on_stream};
+use reqwest::blocking::Client;
+use std::{sync::Arc, time::Duration};
+use url::Url;
+
+use super::{Download, RequestData, ResponseData, Result, RetryPolicy, Transport};
+use crate::v3::{Response, response, request::{self, BricksetRequest, GetMinifigCollectionParameters, SetMinifigCollectionParameters}};
+
+/// Wraps a [`reqwest::blocking::Client`] with convenient functions for accessing the
+/// BrickSet API, including rudimentary session management.
+///
+/// Each function runs the corresponding function of [`super::ClientWrapper`] to completion
+/// on the current thread, so both wrappers share the same retry policy, daily limit, key
+/// cache, automatic re-login and request building. Functions that send several requests
+/// concurrently in [`super::ClientWrapper`] send them one at a time here.
+pub struct ClientWrapper<'a> {
+    client: &'a Client,
+    inner: super::ClientWrapper<'a, Client>,
+}
+
+/// Builds a [`ClientWrapper`] with several options at once. Created by
+/// [`ClientWrapper::builder`]. See [`super::ClientWrapperBuilder`].
+pub struct ClientWrapperBuilder<'a> {
+    client: &'a Client,
+    inner: super::ClientWrapperBuilder<'a, Client>,
+}
+
+/// Sends requests with a [`reqwest::blocking::Client`], blocking the current thread until
+/// the response arrives, and sleeps between retries with [`std::thread::sleep`]. This is
+/// how [`ClientWrapper`] shares its logic with [`super::ClientWrapper`].
+///
+/// Using it with [`super::ClientWrapper`] directly blocks the async runtime's thread, so
+/// only do that outside of an async runtime.
+impl Transport for Client {
+    async fn execute(&self, request: RequestData) -> Result<ResponseData> {
+        let response = Client::execute(self, request.build_blocking(self)?)?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = if status.is_success() {
+            response.bytes()?.into()
+        } else {
+            response.bytes().map(Vec::from).unwrap_or_default()
+        };
+        Ok(ResponseData { status, headers, body })
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+impl<'a> ClientWrapper<'a> {
+    /// Create a new [`ClientWrapper`] that will use the given [`reqwest::blocking::Client`] and API key.
+    /// To set other options up front, use [`Self::builder`].
+    pub fn new(api_key: &'a str, client: &'a Client) -> ClientWrapper<'a> {
+        Self::with_endpoint(api_key, client, request::ENDPOINT.clone())
+    }
+
+    /// Start building a [`ClientWrapper`] that will use the given [`reqwest::blocking::Client`]
+    /// and API key.
+    pub fn builder(api_key: &'a str, client: &'a Client) -> ClientWrapperBuilder<'a> {
+        ClientWrapperBuilder { client, inner: super::ClientWrapper::builder(api_key, client) }
+    }
+
+    /// Same as [`Self::new`], but sends requests to `endpoint` instead of [`request::ENDPOINT`].
+    /// This is useful for testing against a mock server, or for using a mirror of the API.
+    ///
+    /// `endpoint` should end with a trailing slash, e.g. `http://localhost:8080/api/v3.asmx/`.
+    pub fn with_endpoint(api_key: &'a str, client: &'a Client, endpoint: Url) -> ClientWrapper<'a> {
+        ClientWrapper { client, inner: super::ClientWrapper::with_endpoint(api_key, client, endpoint) }
+    }
+
+    /// See [`super::ClientWrapper::set_retry_policy`]. The thread sleeps between retries.
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.inner.set_retry_policy(retry_policy)
+    }
+
+    /// Stop retrying failed requests.
+    pub fn clear_retry_policy(&mut self) {
+        self.inner.clear_retry_policy()
+    }
+
+    /// See [`super::ClientWrapper::set_timeout`].
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.inner.set_timeout(timeout)
+    }
+
+    /// Stop applying a timeout to each request. The [`reqwest::blocking::Client`]'s timeout,
+    /// if any, still applies.
+    pub fn clear_timeout(&mut self) {
+        self.inner.clear_timeout()
+    }
+
+    /// Send `user_agent` as the `User-Agent` header of every request, instead of
+    /// [`super::DEFAULT_USER_AGENT`]. This replaces the [`reqwest::blocking::Client`]'s user
+    /// agent. Returns an error if `user_agent` isn't a valid header value.
+    pub fn set_user_agent(&mut self, user_agent: &str) -> Result<()> {
+        self.inner.set_user_agent(user_agent)
+    }
+
+    /// Stop setting the `User-Agent` header, so requests use the
+    /// [`reqwest::blocking::Client`]'s own user agent, if it has one, instead of
+    /// [`super::DEFAULT_USER_AGENT`].
+    pub fn clear_user_agent(&mut self) {
+        self.inner.clear_user_agent()
+    }
+
+    /// See [`super::ClientWrapper::set_key_cache_ttl`].
+    pub fn set_key_cache_ttl(&mut self, ttl: Duration) {
+        self.inner.set_key_cache_ttl(ttl)
+    }
+
+    /// Stop remembering successful [`Self::check_key`] calls.
+    pub fn clear_key_cache_ttl(&mut self) {
+        self.inner.clear_key_cache_ttl()
+    }
+
+    /// Forget the last successful [`Self::check_key`] call, so the next call sends a request.
+    pub fn invalidate_key_cache(&mut self) {
+        self.inner.invalidate_key_cache()
+    }
+
+    /// See [`super::ClientWrapper::set_daily_limit`].
+    pub fn set_daily_limit(&mut self, limit: usize, enforce: bool) {
+        self.inner.set_daily_limit(limit, enforce)
+    }
+
+    /// Stop tracking the daily request limit.
+    pub fn clear_daily_limit(&mut self) {
+        self.inner.clear_daily_limit()
+    }
+
+    /// The number of requests remaining today, or `None` if no daily limit has been set
+    /// using [`Self::set_daily_limit`].
+    pub fn remaining_quota(&self) -> Option<usize> {
+        self.inner.remaining_quota()
+    }
+
+    /// Check if the [`ClientWrapper`]'s API key is valid.
+    /// 
+    /// This function can be used even when the [`ClientWrapper`] is not logged in. If a
+    /// TTL was set with [`Self::set_key_cache_ttl`], a recent successful check is reused
+    /// instead of sending a new request.
+    pub fn check_key(&self) -> Result<response::CheckKeyResponse> {
+        block_on(self.inner.check_key())
+    }
+    
+    /// Get information about API key usage.
+    /// 
+    /// This function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn get_key_usage_stats(&self) -> Result<response::GetKeyUsageStatsResponse> {
+        block_on(self.inner.get_key_usage_stats())
+    }
+
+    /// Log into Brickset with the given username and password. The resulting user hash token
+    /// will be used for subsequent requests until [`Self::log_out`] is called.
+    pub fn log_in(&mut self, username: &str, password: &str) -> Result<response::LoginResponse> {
+        block_on(self.inner.log_in(username, password))
+    }
+
+    /// Try to log in using an existing user hash token. This function will return
+    /// an error if the token has expired.
+    pub fn reuse_login(&mut self, user_hash: &str) -> Result<response::CheckUserHashResponse> {
+        block_on(self.inner.reuse_login(user_hash))
+    }
+
+    /// Log in using `user_hash` if one is given and it is still valid, otherwise log in
+    /// using `username` and `password`. Returns the user hash token that is now in use,
+    /// which may be worth caching for next time.
+    pub fn log_in_or_reuse(&mut self, user_hash: Option<&str>, username: &str, password: &str) -> Result<response::LoginResponse> {
+        block_on(self.inner.log_in_or_reuse(user_hash, username, password))
+    }
+
+    /// Log in by reusing an existing user hash token. Be careful! This function
+    /// will succeed even if the token is invalid. Consider using [`Self::reuse_login`]
+    /// instead.
+    pub fn force_reuse_login(&mut self, user_hash: &str) {
+        self.inner.force_reuse_login(user_hash)
+    }
+
+    /// Validate a user hash token.
+    ///
+    /// This function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn check_user_hash(&self, user_hash: &str) -> Result<response::CheckUserHashResponse> {
+        block_on(self.inner.check_user_hash(user_hash))
+    }
+
+    /// Same as [`Self::check_user_hash`], but validates the user token currently being used
+    /// by the [`ClientWrapper`].
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn validate_login(&self) -> Result<response::CheckUserHashResponse> {
+        block_on(self.inner.validate_login())
+    }
+
+    /// Forget the saved user hash token. If the [`ClientWrapper`] isn't logged in,
+    /// this function has no effect.
+    pub fn log_out(&mut self) {
+        self.inner.log_out()
+    }
+
+    /// Validate the saved user hash token using [`Self::validate_login`], then forget it.
+    /// If the token is invalid, the error is returned and the token is kept.
+    ///
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn try_log_out(&mut self) -> Result<()> {
+        block_on(self.inner.try_log_out())
+    }
+
+    /// Returns true if the [`ClientWrapper`] is currently logged in
+    #[inline]
+    pub fn is_logged_in(&self) -> bool {
+        self.inner.is_logged_in()
+    }
+
+    /// The saved user hash token, if the [`ClientWrapper`] is logged in. See
+    /// [`super::ClientWrapper::user_hash`].
+    #[inline]
+    pub fn user_hash(&self) -> Option<Arc<str>> {
+        self.inner.user_hash()
+    }
+
+    /// See [`super::ClientWrapper::set_auto_relogin`], including its note about storing the
+    /// password.
+    pub fn set_auto_relogin(&mut self, username: &str, password: &str) {
+        self.inner.set_auto_relogin(username, password)
+    }
+
+    /// Stop logging in again automatically, and forget the username and password given to
+    /// [`Self::set_auto_relogin`].
+    pub fn clear_auto_relogin(&mut self) {
+        self.inner.clear_auto_relogin()
+    }
+
+    /// Retrieve a paginated list of sets, or more information about a particular set. You may
+    /// find these functions convenient for some use cases:
+    /// 
+    /// - [`Self::get_wanted_sets`]
+    /// - [`Self::get_owned_sets`]
+    ///
+    /// If `params` filters by the user's owned or wanted sets, and the [`ClientWrapper`]
+    /// is not logged in, this function will return an error.
+    pub fn get_sets<'s>(&self, params: request::GetSetsParameters<'s>) -> Result<response::GetSetsResponse> {
+        block_on(self.inner.get_sets(params))
+    }
+
+    /// Same as [`Self::get_sets`], but uses `user_hash` instead of the [`ClientWrapper`]'s
+    /// saved user hash token. This function can be used even when the [`ClientWrapper`] is
+    /// not logged in. An empty `user_hash` sends the request without a user.
+    pub fn get_sets_as<'s>(&self, user_hash: &str, params: request::GetSetsParameters<'s>) -> Result<response::GetSetsResponse> {
+        block_on(self.inner.get_sets_as(user_hash, params))
+    }
+
+    /// Retrieve every set matching `params`, one page at a time. Each page is fetched when
+    /// the previous one has been used up. See [`super::ClientWrapper::get_sets_stream`].
+    pub fn get_sets_iter<'s>(&'s self, params: request::GetSetsParameters<'s>) -> impl Iterator<Item = Result<response::Set>> + 's {
+        block_on_stream(Box::pin(self.inner.get_sets_stream(params)))
+    }
+
+    /// Retrieve every set matching `params`, fetching one page after another. Each page is
+    /// fetched with the page size given in `params` (default = 500), and the page number in
+    /// `params` is ignored. If any request fails, the error is returned.
+    pub fn get_all_sets<'s>(&self, params: request::GetSetsParameters<'s>) -> Result<Vec<response::Set>> {
+        block_on(self.inner.get_all_sets(params, 1))
+    }
+
+    /// Get the user's wanted sets. For additional filtering options, use [`Self::get_sets`].
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn get_wanted_sets(&self, order_by: Option<request::OrderBy>, page_size: Option<usize>, page_number: Option<usize>, extended_data: bool) -> Result<response::GetSetsResponse> {
+        block_on(self.inner.get_wanted_sets(order_by, page_size, page_number, extended_data))
+    }
+
+    /// Get the user's owned sets. For additional filtering options, use [`Self::get_sets`].
+    ///
+    /// Version 3 of the BrickSet API has no method for collection totals. The response's
+    /// `matches` is the number of different sets the user owns, even with a small page size.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn get_owned_sets(&self, order_by: Option<request::OrderBy>, page_size: Option<usize>, page_number: Option<usize>, extended_data: bool) -> Result<response::GetSetsResponse> {
+        block_on(self.inner.get_owned_sets(order_by, page_size, page_number, extended_data))
+    }
+
+    /// See [`super::ClientWrapper::get_sets_updated_since`].
+    pub fn get_sets_updated_since(&self, since: DateTime<Utc>, page_size: Option<usize>) -> Result<response::GetSetsResponse> {
+        block_on(self.inner.get_sets_updated_since(since, page_size))
+    }
+
+    /// Get additional images for a particular set.
+    pub fn get_additional_images(&self, set_id: u64) -> Result<response::GetAdditionalImagesResponse> {
+        block_on(self.inner.get_additional_images(set_id))
+    }
+
+    /// Get instructions for a particular set.
+    pub fn get_instructions(&self, set_id: u64) -> Result<response::GetInstructionsResponse> {
+        block_on(self.inner.get_instructions(set_id))
+    }
+
+    /// Get instructions for a particular set.
+    pub fn get_instructions_2(&self, set_number: &str) -> Result<response::GetInstructionsResponse> {
+        block_on(self.inner.get_instructions_2(set_number))
+    }
+
+    /// Get a single set by its ID, or `None` if there is no such set. See
+    /// [`super::ClientWrapper::get_set`].
+    pub fn get_set(&self, set_id: u64, extended: bool) -> Result<Option<response::Set>> {
+        block_on(self.inner.get_set(set_id, extended))
+    }
+
+    /// Get a single set by its set number, or `None` if there is no matching set. See
+    /// [`super::ClientWrapper::get_set_by_number`].
+    pub fn get_set_by_number(&self, number: &str, extended: bool) -> Result<Option<response::Set>> {
+        block_on(self.inner.get_set_by_number(number, extended))
+    }
+
+    /// Look up the set ID of a set, given its full set number including the variant number,
+    /// e.g. `"6876-1"`. Returns `None` if there is no matching set.
+    pub fn resolve_set_id(&self, set_number: &str) -> Result<Option<u64>> {
+        block_on(self.inner.resolve_set_id(set_number))
+    }
+
+    /// Get reviews for a particular set.
+    ///
+    /// The BrickSet API can't page through reviews, so every review of the set is returned
+    /// at once. Popular sets can have many reviews; see [`Self::get_reviews_limited`].
+    ///
+    /// Reviews are read-only: version 3 of the BrickSet API has no method for publishing
+    /// a review, so reviews can only be written on the BrickSet website.
+    pub fn get_reviews(&self, set_id: u64) -> Result<response::GetReviewsResponse> {
+        block_on(self.inner.get_reviews(set_id))
+    }
+
+    /// Same as [`Self::get_reviews`], but keeps at most `max` reviews. See
+    /// [`super::ClientWrapper::get_reviews_limited`].
+    pub fn get_reviews_limited(&self, set_id: u64, max: usize) -> Result<response::GetReviewsResponse> {
+        block_on(self.inner.get_reviews_limited(set_id, max))
+    }
+
+    /// Download the file that `instructions` points to, usually a PDF. See
+    /// [`super::ClientWrapper::download_instructions`].
+    pub fn download_instructions(&self, instructions: &response::Instructions) -> Result<bytes::Bytes> {
+        block_on(self.inner.download_instructions(instructions))
+    }
+
+    /// Same as [`Self::download_instructions`], but takes the file's URL, and skips the
+    /// download if the file hasn't changed. See
+    /// [`super::ClientWrapper::download_instructions_conditional`].
+    pub fn download_instructions_conditional(&self, url: &str, etag: Option<&str>, last_modified: Option<&str>) -> Result<Download> {
+        block_on(self.inner.download_instructions_conditional(url, etag, last_modified))
+    }
+
+    /// Get all themes, with the total number of sets in each theme.
+    pub fn get_themes(&self) -> Result<response::GetThemesResponse> {
+        block_on(self.inner.get_themes())
+    }
+
+    /// Get subthemes for the given theme, with the total number of sets in each subtheme.
+    pub fn get_subthemes(&self, theme: &str) -> Result<response::GetSubthemesResponse> {
+        block_on(self.inner.get_subthemes(theme))
+    }
+
+    /// Get a list of years for a given theme, with the total number of sets in each year.
+    pub fn get_years(&self, theme: &str) -> Result<response::GetYearsResponse> {
+        block_on(self.inner.get_years(theme))
+    }
+
+    /// Get a theme, its subthemes, and the years it was active. Returns an error if there is
+    /// no theme named `theme` (ignoring ASCII case).
+    ///
+    /// This sends `getThemes`, `getSubthemes` and `getYears` requests one after another, so
+    /// it counts as three API calls against the daily quota.
+    pub fn get_theme_tree(&self, theme: &str) -> Result<response::ThemeTree> {
+        block_on(self.inner.get_theme_tree(theme))
+    }
+
+    /// Alter the user's collection. You may find these functions more convenient:
+    /// - [`Self::set_wanted`]
+    /// - [`Self::set_owned`]
+    /// - [`Self::set_notes`]
+    /// - [`Self::set_rating`]
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn set_collection<'s>(&self, set_id: u64, params: request::SetCollectionParameters<'s>) -> Result<response::SetCollectionResponse> {
+        block_on(self.inner.set_collection(set_id, params))
+    }
+
+    /// Same as [`Self::set_collection`], but alters the collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn set_collection_as<'s>(&self, user_hash: &str, set_id: u64, params: request::SetCollectionParameters<'s>) -> Result<response::SetCollectionResponse> {
+        block_on(self.inner.set_collection_as(user_hash, set_id, params))
+    }
+
+    /// Alter the user's collection for many sets at once, sending one `setCollection`
+    /// request per item, one after another. Each request counts against the daily quota.
+    ///
+    /// Returns one result per item, in the same order as `items`. A failed request doesn't
+    /// stop the others from being sent. If the [`ClientWrapper`] is not logged in, every
+    /// result will be an error.
+    pub fn set_collection_many<'s>(&self, items: impl IntoIterator<Item = (u64, request::SetCollectionParameters<'s>)>) -> Vec<Result<response::SetCollectionResponse>> {
+        block_on(self.inner.set_collection_many(items, 1))
+    }
+
+    /// Same as [`Self::set_collection_many`], but alters the collection of the user identified
+    /// by `user_hash`.
+    pub fn set_collection_many_as<'s>(&self, user_hash: &str, items: impl IntoIterator<Item = (u64, request::SetCollectionParameters<'s>)>) -> Vec<Result<response::SetCollectionResponse>> {
+        block_on(self.inner.set_collection_many_as(user_hash, items, 1))
+    }
+
+    /// Add or remove a set from the user's wanted list.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_wanted(&self, set_id: u64, wanted: bool) -> Result<response::SetCollectionResponse> {
+        let params = request::SetCollectionParameters::new()
+            .wanted(wanted);
+        self.set_collection(set_id, params)
+    }
+
+    /// Add or remove a set from the user's owned list.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_owned(&self, set_id: u64, qty_owned: usize) -> Result<response::SetCollectionResponse> {
+        let params = request::SetCollectionParameters::new()
+            .owned(qty_owned);
+        self.set_collection(set_id, params)
+    }
+
+    /// Modify the user's notes for a set.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_notes(&self, set_id: u64, notes: &str) -> Result<response::SetCollectionResponse> {
+        let params = request::SetCollectionParameters::new()
+            .notes(notes);
+        self.set_collection(set_id, params)
+    }
+
+    /// Modify the user's rating of a set.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_rating(&self, set_id: u64, rating: i32) -> Result<response::SetCollectionResponse> {
+        let params = request::SetCollectionParameters::new()
+            .rating(rating);
+        self.set_collection(set_id, params)
+    }
+
+    /// Get the user's set notes.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn get_notes(&self) -> Result<response::GetUserNotesResponse> {
+        block_on(self.inner.get_notes())
+    }
+
+    /// Same as [`Self::get_notes`], but gets the set notes of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn get_notes_as(&self, user_hash: &str) -> Result<response::GetUserNotesResponse> {
+        block_on(self.inner.get_notes_as(user_hash))
+    }
+
+    /// Get the user's minifig collection. You may find these functions more convenient:
+    /// 
+    /// - [`Self::get_owned_minifigs`]
+    /// - [`Self::get_wanted_minifigs`]
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn get_minifig_collection<'s>(&self, params: GetMinifigCollectionParameters<'s>) -> Result<response::GetMinifigCollectionResponse> {
+        block_on(self.inner.get_minifig_collection(params))
+    }
+
+    /// Same as [`Self::get_minifig_collection`], but gets the minifig collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn get_minifig_collection_as<'s>(&self, user_hash: &str, params: GetMinifigCollectionParameters<'s>) -> Result<response::GetMinifigCollectionResponse> {
+        block_on(self.inner.get_minifig_collection_as(user_hash, params))
+    }
+
+    /// Get a list of minifigs owned by the user. If not None, `query` is used to filter the
+    /// results by name and ID.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn get_owned_minifigs(&self, query: Option<&str>) -> Result<response::GetMinifigCollectionResponse> {
+        let mut params = request::GetMinifigCollectionParameters::owned();
+        if let Some(query) = query {
+            params = params.query(query);
+        }
+        self.get_minifig_collection(params)
+    }
+
+    /// Get a list of minifigs owned by the user. If not None, `query` is used to filter the
+    /// results by name and ID.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn get_wanted_minifigs(&self, query: Option<&str>) -> Result<response::GetMinifigCollectionResponse> {
+        let mut params = request::GetMinifigCollectionParameters::wanted();
+        if let Some(query) = query {
+            params = params.query(query);
+        }
+        self.get_minifig_collection(params)
+    }
+
+    /// Modify the user's minifig collection. You may find these functions more convenient:
+    /// 
+    /// - [`Self::set_minifig_owned`]
+    /// - [`Self::set_minifig_wanted`]
+    /// - [`Self::set_minifig_notes`]
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn set_minifig_collection<'s>(&self, minifig_number: &str, params: SetMinifigCollectionParameters<'s>) -> Result<response::SetMinifigCollectionResponse> {
+        block_on(self.inner.set_minifig_collection(minifig_number, params))
+    }
+
+    /// Same as [`Self::set_minifig_collection`], but alters the minifig collection of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn set_minifig_collection_as<'s>(&self, user_hash: &str, minifig_number: &str, params: SetMinifigCollectionParameters<'s>) -> Result<response::SetMinifigCollectionResponse> {
+        block_on(self.inner.set_minifig_collection_as(user_hash, minifig_number, params))
+    }
+
+    /// Add or remove a minifig from the user's owned list.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_minifig_owned(&self, minifig_number: &str, qty_owned: usize) -> Result<response::SetMinifigCollectionResponse> {
+        let params = request::SetMinifigCollectionParameters::new()
+            .owned(qty_owned);
+        self.set_minifig_collection(minifig_number, params)
+    }
+
+    /// Add or remove a minifig from the user's wanted list.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_minifig_wanted(&self, minifig_number: &str, wanted: bool) -> Result<response::SetMinifigCollectionResponse> {
+        let params = request::SetMinifigCollectionParameters::new()
+            .wanted(wanted);
+        self.set_minifig_collection(minifig_number, params)
+    }
+
+    /// Modify the user's notes for a minifig.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    #[inline]
+    pub fn set_minifig_notes(&self, minifig_number: &str, notes: &str) -> Result<response::SetMinifigCollectionResponse> {
+        let params = request::SetMinifigCollectionParameters::new()
+            .notes(notes);
+        self.set_minifig_collection(minifig_number, params)
+    }
+
+    /// Get the user's minifig notes.
+    /// 
+    /// If the [`ClientWrapper`] is not logged in, this function will return an error.
+    pub fn get_minifig_notes(&self) -> Result<response::GetMinifigUserNotesResponse> {
+        block_on(self.inner.get_minifig_notes())
+    }
+
+    /// Same as [`Self::get_minifig_notes`], but gets the minifig notes of the user identified by `user_hash`.
+    /// `user_hash` is used instead of the [`ClientWrapper`]'s saved user hash token, so this
+    /// function can be used even when the [`ClientWrapper`] is not logged in.
+    pub fn get_minifig_notes_as(&self, user_hash: &str) -> Result<response::GetMinifigUserNotesResponse> {
+        block_on(self.inner.get_minifig_notes_as(user_hash))
+    }
+
+    /// Build the [`reqwest::blocking::Request`] that [`Self::execute`] would send for
+    /// `request`, without sending it. See [`super::ClientWrapper::prepare`].
+    pub fn prepare<E: BricksetRequest>(&self, request: E) -> Result<reqwest::blocking::Request> {
+        Ok(self.inner.prepare_data(&request)?.build_blocking(self.client)?)
+    }
+
+    /// Send any [`BricksetRequest`]. See [`super::ClientWrapper::execute`].
+    pub fn execute<E, R>(&self, request: E) -> Result<Response<R>>
+    where
+        R: serde::de::DeserializeOwned,
+        E: BricksetRequest
+    {
+        block_on(self.inner.execute(request))
+    }
+}
+
+impl<'a> ClientWrapperBuilder<'a> {
+    /// See [`super::ClientWrapperBuilder::endpoint`].
+    pub fn endpoint(mut self, endpoint: Url) -> Self {
+        self.inner = self.inner.endpoint(endpoint);
+        self
+    }
+
+    /// See [`ClientWrapper::force_reuse_login`].
+    pub fn user_hash(mut self, user_hash: &str) -> Self {
+        self.inner = self.inner.user_hash(user_hash);
+        self
+    }
+
+    /// See [`ClientWrapper::set_auto_relogin`].
+    pub fn auto_relogin(mut self, username: &str, password: &str) -> Self {
+        self.inner = self.inner.auto_relogin(username, password);
+        self
+    }
+
+    /// See [`ClientWrapper::set_retry_policy`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.inner = self.inner.retry_policy(retry_policy);
+        self
+    }
+
+    /// See [`ClientWrapper::set_daily_limit`].
+    pub fn daily_limit(mut self, limit: usize, enforce: bool) -> Self {
+        self.inner = self.inner.daily_limit(limit, enforce);
+        self
+    }
+
+    /// See [`ClientWrapper::set_timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
+
+    /// See [`ClientWrapper::set_key_cache_ttl`].
+    pub fn key_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.inner = self.inner.key_cache_ttl(ttl);
+        self
+    }
+
+    /// See [`ClientWrapper::set_user_agent`].
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.inner = self.inner.user_agent(user_agent);
+        self
+    }
+
+    /// See [`ClientWrapper::clear_user_agent`].
+    pub fn client_user_agent(mut self) -> Self {
+        self.inner = self.inner.client_user_agent();
+        self
+    }
+
+    /// Build the [`ClientWrapper`]. Returns an error if the options can't work together;
+    /// see [`super::ClientWrapperBuilder::build`].
+    pub fn build(self) -> Result<ClientWrapper<'a>> {
+        Ok(ClientWrapper { client: self.client, inner: self.inner.build()? })
+    }
+}
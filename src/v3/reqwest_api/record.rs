@@ -0,0 +1,130 @@
+//! [`Transport`]s for recording BrickSet responses and playing them back, VCR-style.
+//! Enabled by the `record` feature.
+//!
+//! [`RecordingTransport`] wraps another [`Transport`], usually a [`reqwest::Client`], and
+//! writes the body of every successful response to a directory, in a file named after the
+//! API method, e.g. `getSets.json`. [`PlaybackTransport`] reads those files back, so tests
+//! can run against real BrickSet responses without a network connection or an API key.
+//! Each method has one file, so recording the same method twice keeps the last response.
+//!
+//! # Regenerating fixtures
+//!
+//! 1. Run the code under test with a [`ClientWrapper`](super::ClientWrapper) that sends
+//!    requests through a [`RecordingTransport`] with a real API key, pointing at the
+//!    fixture directory.
+//! 2. Check the new files before committing them. Recorded responses contain whatever
+//!    BrickSet sent, including the user hash returned by `login`, so delete or edit
+//!    anything that shouldn't be shared.
+//! 3. Run the tests with a [`PlaybackTransport`] pointing at the same directory. Enabling
+//!    the `strict-parsing` feature as well catches fields BrickSet has added since the
+//!    response structs were last updated.
+//!
+//! ```no_run
+//! use brickset::reqwest_api::{ClientWrapper, record::{PlaybackTransport, RecordingTransport}};
+//!
+//! # async fn example() -> Result<(), brickset::reqwest_api::Error> {
+//! // Record
+//! let recorder = RecordingTransport::new(reqwest::Client::new(), "tests/fixtures")?;
+//! let client = ClientWrapper::new("<your API key>", &recorder);
+//! client.get_themes().await?;
+//!
+//! // Play back
+//! let playback = PlaybackTransport::new("tests/fixtures");
+//! let client = ClientWrapper::new("<any API key>", &playback);
+//! let themes = client.get_themes().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Files are read and written with [`std::fs`], blocking the async task while they are.
+//! This is fine for tests, but these transports aren't meant for production use.
+
+use reqwest::Client;
+use std::path::{Path, PathBuf};
+
+use super::{RequestData, ResponseData, Result, Transport};
+
+/// A [`Transport`] that sends requests through another [`Transport`], and saves the body of
+/// every successful response to a directory. See the [module documentation](self).
+pub struct RecordingTransport<T = Client> {
+    inner: T,
+    dir: PathBuf,
+}
+
+/// A [`Transport`] that answers requests with responses saved by a [`RecordingTransport`],
+/// without sending anything. See the [module documentation](self).
+pub struct PlaybackTransport {
+    dir: PathBuf,
+}
+
+impl<T: Transport + Sync> RecordingTransport<T> {
+    /// Create a new [`RecordingTransport`] that sends requests through `inner`, and saves
+    /// responses in `dir`. `dir` is created if it doesn't exist.
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(RecordingTransport { inner, dir })
+    }
+
+    /// The [`Transport`] requests are sent through.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// The directory responses are saved in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl<T: Transport + Sync> Transport for RecordingTransport<T> {
+    async fn execute(&self, request: RequestData) -> Result<ResponseData> {
+        let path = fixture_path(&self.dir, &request)?;
+        let response = self.inner.execute(request).await?;
+        if response.status.is_success() {
+            std::fs::write(path, &response.body)?;
+        }
+        Ok(response)
+    }
+
+    fn sleep(&self, duration: std::time::Duration) -> impl std::future::Future<Output = ()> + Send {
+        self.inner.sleep(duration)
+    }
+}
+
+impl PlaybackTransport {
+    /// Create a new [`PlaybackTransport`] that reads responses from `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        PlaybackTransport { dir: dir.into() }
+    }
+
+    /// The directory responses are read from.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Transport for PlaybackTransport {
+    /// Respond with the saved response for the request's method, with a 200 status code.
+    /// Returns [`Error::Io`](super::Error::Io) if no response has been saved for the method.
+    async fn execute(&self, request: RequestData) -> Result<ResponseData> {
+        let path = fixture_path(&self.dir, &request)?;
+        let body = std::fs::read(path)?;
+        Ok(ResponseData {
+            status: reqwest::StatusCode::OK,
+            headers: Default::default(),
+            body,
+        })
+    }
+}
+
+/// The file a response to `request` is saved in: the last segment of the request's URL,
+/// which is the API method name, with a `.json` extension.
+fn fixture_path(dir: &Path, request: &RequestData) -> Result<PathBuf> {
+    let method = request.url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|method| !method.is_empty())
+        .ok_or_else(|| crate::request::Error::Message(format!("No method name in {}", request.url)))?;
+    Ok(dir.join(format!("{method}.json")))
+}
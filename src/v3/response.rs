@@ -1,24 +1,63 @@
 //! Response parsers.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use super::util;
+use std::{collections::{BTreeMap, HashMap, HashSet}, ops::RangeInclusive};
+use super::{request::{GetSetsParameters, SetId}, util};
+use url::Url;
+
+#[cfg(feature = "test-fixtures")]
+pub mod fixtures;
 
 /// Response to a failed request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Error {
     pub message: String,
 }
 
+impl Error {
+    /// Classify the error based on its message. Rate limiting is checked first, because
+    /// BrickSet's rate limit messages can also mention the API key.
+    pub fn kind(&self) -> ApiErrorKind {
+        let message = self.message.to_ascii_lowercase();
+        let contains_any = |patterns: &[&str]| patterns.iter().any(|pattern| message.contains(pattern));
+        if contains_any(&["limit exceeded", "request limit"]) {
+            ApiErrorKind::RateLimited
+        } else if contains_any(&["invalid api key", "invalid apikey"]) {
+            ApiErrorKind::InvalidApiKey
+        } else if contains_any(&["invalid user hash", "invalid userhash"]) {
+            ApiErrorKind::InvalidUserHash
+        } else {
+            ApiErrorKind::Other
+        }
+    }
+}
+
+/// The category of an [`Error`], as reported by [`Error::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiErrorKind {
+    /// The API key is invalid.
+    InvalidApiKey,
+    /// The user hash token is invalid or has expired. The user needs to log in again.
+    InvalidUserHash,
+    /// The API key has exceeded its daily request limit.
+    RateLimited,
+    /// Any other error.
+    Other,
+}
+
 /// Response to a successful `checkKey` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct CheckKeyResponse {}
 
 /// Response to a successful `login` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct LoginResponse {
     pub hash: String
 }
@@ -26,11 +65,13 @@ pub struct LoginResponse {
 /// Response to a successful `checkUserHash` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct CheckUserHashResponse {}
 
 /// Response to a successful `getKeyUsageStats` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetKeyUsageStatsResponse {
     pub matches: usize,
     pub api_key_usage: Vec<ApiKeyUsage>
@@ -38,6 +79,7 @@ pub struct GetKeyUsageStatsResponse {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct ApiKeyUsage {
     pub date_stamp: DateTime<Utc>,
     pub count: usize
@@ -46,6 +88,7 @@ pub struct ApiKeyUsage {
 /// Response to a successful `getSets` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetSetsResponse {
     pub matches: usize,
     pub sets: Vec<Set>
@@ -54,6 +97,7 @@ pub struct GetSetsResponse {
 /// Response to a successful `getAdditionalImages` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetAdditionalImagesResponse {
     pub matches: usize,
     pub additional_images: Vec<Image>
@@ -62,6 +106,7 @@ pub struct GetAdditionalImagesResponse {
 /// Response to a successful `getInstructions` or `getInstructions2` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetInstructionsResponse {
     pub matches: usize,
     pub instructions: Vec<Instructions>
@@ -70,6 +115,7 @@ pub struct GetInstructionsResponse {
 /// Response to a successful `getReviews` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetReviewsResponse {
     pub matches: usize,
     pub reviews: Vec<Review>
@@ -78,6 +124,7 @@ pub struct GetReviewsResponse {
 /// Response to a successful `getThemes` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetThemesResponse {
     pub matches: usize,
     pub themes: Vec<Theme>
@@ -86,6 +133,7 @@ pub struct GetThemesResponse {
 /// Response to a successful `getSubthemes` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetSubthemesResponse {
     pub matches: usize,
     pub subthemes: Vec<Subtheme>
@@ -94,19 +142,32 @@ pub struct GetSubthemesResponse {
 /// Response to a successful `getYears` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetYearsResponse {
     pub matches: usize,
     pub years: Vec<Year>
 }
 
+/// A theme, along with its subthemes and the years it was active. Retrieved by
+/// `ClientWrapper::get_theme_tree`.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeTree {
+    pub theme: Theme,
+    pub subthemes: Vec<Subtheme>,
+    pub years: Vec<Year>,
+}
+
 /// Response to a successful `setCollection` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct SetCollectionResponse {}
 
 /// Response to a successful `getUserNotes` request.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetUserNotesResponse {
     pub matches: usize,
     pub user_notes: Vec<UserNote>
@@ -114,6 +175,7 @@ pub struct GetUserNotesResponse {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetMinifigCollectionResponse {
     pub matches: usize,
     pub minifigs: Vec<MinifigCollection>
@@ -121,10 +183,12 @@ pub struct GetMinifigCollectionResponse {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct SetMinifigCollectionResponse {}
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct GetMinifigUserNotesResponse {
     pub matches: usize,
     pub user_minifig_notes: Vec<UserMinifigNote>
@@ -132,8 +196,10 @@ pub struct GetMinifigUserNotesResponse {
 
 
 
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Set {
     #[serde(rename = "setID")]
     pub set_id: u64,
@@ -165,10 +231,15 @@ pub struct Set {
     pub collections: Collections,
     #[serde(rename = "LEGOCom")]
     pub lego_com: LegoCom,
-    pub rating: f64,
+    /// The set's average rating by BrickSet users, or `None` if nobody has rated it.
+    #[serde(default)]
+    #[serde(serialize_with = "util::zero_none::serialize", deserialize_with = "util::lenient_f64::deserialize_zero_none")]
+    pub rating: Option<f64>,
     pub review_count: usize,
+    #[serde(default)]
     #[serde(with = "util::not_specified_optional_string")]
     pub packaging_type: Option<String>,
+    #[serde(default)]
     #[serde(with = "util::not_specified_optional_string")]
     pub availability: Option<String>,
     pub instructions_count: usize,
@@ -182,6 +253,7 @@ pub struct Set {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Image {
     #[serde(rename = "thumbnailURL")]
     #[serde(default)]
@@ -193,6 +265,7 @@ pub struct Image {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Collection {
     #[serde(default)]
     #[serde(rename = "owned")]
@@ -203,13 +276,21 @@ pub struct Collection {
     #[serde(default)]
     pub qty_owned: Option<usize>,
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub rating: Option<f64>,
     #[serde(default)]
     pub notes: Option<String>,
+    /// The ID of the user's collection entry for this set, which is what
+    /// [`crate::request::OrderBy::CollectionID`] sorts by. Only present for sets in the
+    /// user's collection.
+    #[serde(default)]
+    #[serde(rename = "collectionID", alias = "collectionId")]
+    pub collection_id: Option<u64>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Collections {
     #[serde(default)]
     pub owned_by: Option<usize>,
@@ -218,6 +299,7 @@ pub struct Collections {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct LegoCom {
     #[serde(rename = "US")]
     pub united_states: LegoComDetails,
@@ -227,12 +309,27 @@ pub struct LegoCom {
     pub canada: LegoComDetails,
     #[serde(rename = "DE")]
     pub germany: LegoComDetails,
+    #[serde(rename = "FR")]
+    #[serde(default)]
+    pub france: LegoComDetails,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+/// A region with its own LEGO.com store, as reported in [`LegoCom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    US,
+    UK,
+    CA,
+    DE,
+    FR,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct LegoComDetails {
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub retail_price: Option<f64>,
     #[serde(default)]
     pub date_first_available: Option<DateTime<Utc>>,
@@ -241,26 +338,39 @@ pub struct LegoComDetails {
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct AgeRange {
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub min: Option<f64>,
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub max: Option<f64>,
 }
 
+/// The dimensions of a set's box. BrickSet reports lengths in centimeters, and weight in
+/// kilograms; see [`Dimensions::to_imperial`] for inches and ounces.
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Dimensions {
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub height: Option<f64>,
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub width: Option<f64>,
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub depth: Option<f64>,
+    /// The weight of the boxed set in kilograms, not grams. BrickSet reports weights like
+    /// 1.945 for a set of over 1,000 pieces, which only makes sense in kilograms.
     #[serde(default)]
+    #[serde(with = "util::lenient_f64")]
     pub weight: Option<f64>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Barcode {
     #[serde(default)]
     #[serde(rename = "UPC")]
@@ -272,6 +382,7 @@ pub struct Barcode {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct ExtendedData {
     #[serde(default)]
     pub description: Option<String>,
@@ -283,6 +394,7 @@ pub struct ExtendedData {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Instructions {
     #[serde(rename = "URL")]
     pub url: String,
@@ -292,6 +404,7 @@ pub struct Instructions {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Review {
     pub author: String,
     pub date_posted: DateTime<Utc>,
@@ -304,6 +417,7 @@ pub struct Review {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Theme {
     #[serde(rename = "theme")]
     pub name: String,
@@ -315,6 +429,7 @@ pub struct Theme {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Subtheme {
     pub theme: String,
     #[serde(rename = "subtheme")]
@@ -326,6 +441,7 @@ pub struct Subtheme {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Year {
     pub theme: String,
     pub year: i32,
@@ -334,7 +450,10 @@ pub struct Year {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct Rating {
+    /// The reviewer's overall score, from 1 to 5. Unlike the other scores, zero isn't mapped
+    /// to `None`: a zero `overall` score means the reviewer didn't give one.
     pub overall: i32,
     #[serde(with = "util::zero_none")]
     pub parts: Option<i32>,
@@ -348,6 +467,7 @@ pub struct Rating {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct UserNote {
     #[serde(rename = "setID")]
     pub set_id: u64,
@@ -356,6 +476,7 @@ pub struct UserNote {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct MinifigCollection {
     pub minifig_number: String,
     pub name: String,
@@ -368,11 +489,658 @@ pub struct MinifigCollection {
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "strict-parsing", serde(deny_unknown_fields))]
 pub struct UserMinifigNote {
     pub minifig_number: String,
     pub notes: String
 }
 
+/// Collection-like functions for responses that are a list of `$item`s. `len` and `is_empty`
+/// only count the items in the response itself, not every match (see `matches`).
+macro_rules! list_response {
+    ($response:ident, $field:ident, $into:ident, $item:ty) => {
+        impl $response {
+            #[doc = concat!("Take the `", stringify!($field), "` out of the response.")]
+            #[inline]
+            pub fn $into(self) -> Vec<$item> {
+                self.$field
+            }
+
+            #[doc = concat!("Iterate over the `", stringify!($field), "` in the response.")]
+            #[inline]
+            pub fn iter(&self) -> std::slice::Iter<'_, $item> {
+                self.$field.iter()
+            }
+
+            #[doc = concat!("The number of `", stringify!($field), "` in the response. This can be less than `matches` if the results are paginated.")]
+            #[inline]
+            pub fn len(&self) -> usize {
+                self.$field.len()
+            }
+
+            #[doc = concat!("Returns true if the response has no `", stringify!($field), "`.")]
+            #[inline]
+            pub fn is_empty(&self) -> bool {
+                self.$field.is_empty()
+            }
+        }
+
+        impl IntoIterator for $response {
+            type Item = $item;
+            type IntoIter = std::vec::IntoIter<$item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.$field.into_iter()
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $response {
+            type Item = &'a $item;
+            type IntoIter = std::slice::Iter<'a, $item>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.$field.iter()
+            }
+        }
+    };
+}
+
+list_response!(GetKeyUsageStatsResponse, api_key_usage, into_api_key_usage, ApiKeyUsage);
+list_response!(GetSetsResponse, sets, into_sets, Set);
+list_response!(GetAdditionalImagesResponse, additional_images, into_additional_images, Image);
+list_response!(GetInstructionsResponse, instructions, into_instructions, Instructions);
+list_response!(GetReviewsResponse, reviews, into_reviews, Review);
+list_response!(GetThemesResponse, themes, into_themes, Theme);
+list_response!(GetSubthemesResponse, subthemes, into_subthemes, Subtheme);
+list_response!(GetYearsResponse, years, into_years, Year);
+list_response!(GetUserNotesResponse, user_notes, into_user_notes, UserNote);
+list_response!(GetMinifigCollectionResponse, minifigs, into_minifigs, MinifigCollection);
+list_response!(GetMinifigUserNotesResponse, user_minifig_notes, into_user_minifig_notes, UserMinifigNote);
+
+impl GetKeyUsageStatsResponse {
+    /// The total number of API calls in the response.
+    pub fn total(&self) -> usize {
+        self.api_key_usage.iter().map(|usage| usage.count).sum()
+    }
+
+    /// The number of API calls made on `date` (UTC).
+    pub fn on(&self, date: NaiveDate) -> usize {
+        self.api_key_usage.iter()
+            .filter(|usage| usage.date_stamp.date_naive() == date)
+            .map(|usage| usage.count)
+            .sum()
+    }
+
+    /// The number of API calls made today (UTC).
+    pub fn today(&self) -> usize {
+        self.on(Utc::now().date_naive())
+    }
+}
+
+impl GetSetsResponse {
+    /// The number of pages needed to retrieve all [`Self::matches`] with the given page size.
+    /// Returns 0 if `page_size` is 0.
+    pub fn page_count(&self, page_size: usize) -> usize {
+        if page_size == 0 {
+            0
+        } else {
+            self.matches.div_ceil(page_size)
+        }
+    }
+
+    /// Returns true if there are more pages after `page_number`. Page numbers start at 1.
+    pub fn has_more(&self, page_number: usize, page_size: usize) -> bool {
+        page_number < self.page_count(page_size)
+    }
+
+    /// The sets that have a piece count. BrickSet can't filter by this, so this filtering
+    /// is done client-side.
+    pub fn with_pieces(&self) -> impl Iterator<Item = &Set> {
+        self.sets.iter().filter(|set| set.pieces.is_some())
+    }
+
+    /// The sets that have a minifig count. BrickSet can't filter by this, so this filtering
+    /// is done client-side.
+    pub fn with_minifigs(&self) -> impl Iterator<Item = &Set> {
+        self.sets.iter().filter(|set| set.minifigs.is_some())
+    }
+
+    /// The sets that have at least one of `tags`, ignoring ASCII case. This is a client-side
+    /// filter over the sets already retrieved: sets only include tags when
+    /// [`crate::request::GetSetsParameters::extended_data`] is set, and BrickSet can only
+    /// filter by a single tag (see [`crate::request::GetSetsParameters::tag`]).
+    pub fn filter_by_any_tag(&self, tags: &[&str]) -> Vec<&Set> {
+        self.sets.iter()
+            .filter(|set| tags.iter().any(|tag| set.has_tag(tag)))
+            .collect()
+    }
+
+    /// The sets grouped by theme. Sets without a theme are grouped under `"Unknown"`.
+    pub fn group_by_theme(&self) -> BTreeMap<String, Vec<&Set>> {
+        let mut groups: BTreeMap<String, Vec<&Set>> = BTreeMap::new();
+        for set in &self.sets {
+            let theme = set.theme.as_deref().unwrap_or("Unknown");
+            match groups.get_mut(theme) {
+                Some(group) => group.push(set),
+                None => { groups.insert(theme.to_string(), vec![set]); }
+            }
+        }
+        groups
+    }
+
+    /// The sets grouped by the year they were released.
+    pub fn group_by_year(&self) -> BTreeMap<i32, Vec<&Set>> {
+        let mut groups: BTreeMap<i32, Vec<&Set>> = BTreeMap::new();
+        for set in &self.sets {
+            groups.entry(set.year).or_default().push(set);
+        }
+        groups
+    }
+}
+
+impl GetInstructionsResponse {
+    /// The instructions, without entries whose URL is the same as an earlier entry.
+    pub fn deduped(&self) -> Vec<&Instructions> {
+        let mut seen = HashSet::new();
+        self.instructions.iter()
+            .filter(|instructions| seen.insert(instructions.url.as_str()))
+            .collect()
+    }
+
+    /// The instructions, sorted by description, then by URL.
+    pub fn sorted_by_description(&self) -> Vec<&Instructions> {
+        let mut sorted: Vec<&Instructions> = self.instructions.iter().collect();
+        sorted.sort_by(|a, b| a.description.cmp(&b.description).then_with(|| a.url.cmp(&b.url)));
+        sorted
+    }
+}
+
+impl GetReviewsResponse {
+    /// The average [`Rating::overall`] score of the reviews. Reviews without an overall score
+    /// are ignored. Returns `None` if no review has an overall score.
+    pub fn average_overall(&self) -> Option<f64> {
+        let scores: Vec<i32> = self.reviews.iter()
+            .map(|review| review.rating.overall)
+            .filter(|&overall| overall > 0)
+            .collect();
+        if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<i32>() as f64 / scores.len() as f64)
+        }
+    }
+
+    /// The number of reviews with each [`Rating::overall`] score, indexed by score. Index 0
+    /// counts reviews without an overall score. Scores outside of 0-5 aren't counted.
+    pub fn rating_histogram(&self) -> [usize; 6] {
+        let mut histogram = [0; 6];
+        for review in self.reviews.iter() {
+            if let Some(count) = usize::try_from(review.rating.overall).ok().and_then(|i| histogram.get_mut(i)) {
+                *count += 1;
+            }
+        }
+        histogram
+    }
+}
+
+impl GetThemesResponse {
+    /// Find a theme by name, ignoring ASCII case.
+    pub fn find(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Convert the response into a map of themes, keyed by [`Theme::name`].
+    pub fn into_map(self) -> HashMap<String, Theme> {
+        self.themes.into_iter().map(|theme| (theme.name.clone(), theme)).collect()
+    }
+}
+
+impl GetSubthemesResponse {
+    /// Find a subtheme by name, ignoring ASCII case.
+    pub fn find(&self, name: &str) -> Option<&Subtheme> {
+        self.subthemes.iter().find(|subtheme| subtheme.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl GetYearsResponse {
+    /// Find the entry for a particular year.
+    pub fn find_year(&self, year: i32) -> Option<&Year> {
+        self.years.iter().find(|y| y.year == year)
+    }
+
+    /// The earliest year with any sets, or `None` if there are no years.
+    pub fn earliest(&self) -> Option<i32> {
+        self.years.iter().map(|y| y.year).min()
+    }
+
+    /// The latest year with any sets, or `None` if there are no years.
+    pub fn latest(&self) -> Option<i32> {
+        self.years.iter().map(|y| y.year).max()
+    }
+
+    /// The total number of sets across all years.
+    pub fn total_sets(&self) -> usize {
+        self.years.iter().map(|y| y.set_count).sum()
+    }
+}
+
+/// Something that was active over a range of years, like a [`Theme`] or [`Subtheme`].
+pub trait HasYearSpan {
+    /// The first year with any sets.
+    fn year_from(&self) -> i32;
+
+    /// The last year with any sets.
+    fn year_to(&self) -> i32;
+
+    /// Every year from [`Self::year_from`] to [`Self::year_to`], inclusive.
+    fn year_span(&self) -> RangeInclusive<i32> {
+        self.year_from()..=self.year_to()
+    }
+
+    /// Returns true if `year` is within [`Self::year_span`].
+    fn is_active(&self, year: i32) -> bool {
+        self.year_span().contains(&year)
+    }
+}
+
+impl HasYearSpan for Theme {
+    fn year_from(&self) -> i32 {
+        self.year_from
+    }
+
+    fn year_to(&self) -> i32 {
+        self.year_to
+    }
+}
+
+impl HasYearSpan for Subtheme {
+    fn year_from(&self) -> i32 {
+        self.year_from
+    }
+
+    fn year_to(&self) -> i32 {
+        self.year_to
+    }
+}
+
+impl Set {
+    /// The set's ID, as a [`SetId`].
+    pub fn id(&self) -> SetId {
+        SetId(self.set_id)
+    }
+
+    /// Parameters for finding sets like this one: sets in the same theme, and the same
+    /// subtheme if it has one. BrickSet can't exclude a set from the results, so this set
+    /// will be among them. The parameters can be customized further before sending them.
+    pub fn similar_query(&self) -> GetSetsParameters<'_> {
+        let mut params = GetSetsParameters::new();
+        if let Some(theme) = &self.theme {
+            params = params.theme(theme.as_str());
+        }
+        if let Some(subtheme) = &self.subtheme {
+            params = params.subtheme(subtheme.as_str());
+        }
+        params
+    }
+
+    /// The ID of the user's collection entry for this set. See [`Collection::collection_id`].
+    pub fn collection_id(&self) -> Option<u64> {
+        self.collection.collection_id
+    }
+
+    /// Returns true if the logged-in user owns this set.
+    pub fn is_owned(&self) -> bool {
+        self.collection.is_owned.unwrap_or(false)
+    }
+
+    /// Returns true if this set is on the logged-in user's wanted list.
+    pub fn is_wanted(&self) -> bool {
+        self.collection.is_wanted.unwrap_or(false)
+    }
+
+    /// The number of copies of this set owned by the logged-in user.
+    pub fn qty_owned(&self) -> usize {
+        self.collection.qty_owned.unwrap_or(0)
+    }
+
+    /// Returns true if the set has the given tag, ignoring ASCII case. See
+    /// [`ExtendedData::has_tag`].
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.extended_data.has_tag(tag)
+    }
+
+    /// The set's retail price on LEGO.com in the given region, in the region's currency
+    /// (see [`Region::currency_code`]).
+    pub fn retail_price(&self, region: Region) -> Option<f64> {
+        self.lego_com.get(region).retail_price
+    }
+
+    /// The set's retail price on LEGO.com in the given region, divided by the number of pieces.
+    /// This is the value used by the `*PricePerPiece` variants of [`crate::request::OrderBy`].
+    pub fn price_per_piece(&self, region: Region) -> Option<f64> {
+        match (self.retail_price(region), self.pieces) {
+            (Some(price), Some(pieces)) if pieces > 0 => Some(price / pieces as f64),
+            _ => None,
+        }
+    }
+}
+
+impl AgeRange {
+    /// The age range formatted for display: `"6-12"`, `"18+"` if there's no maximum, or
+    /// `"up to 12"` if there's no minimum. Returns `None` if neither is present. Whole
+    /// numbers are formatted without a trailing `.0`.
+    pub fn display(&self) -> Option<String> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => Some(format!("{min}-{max}")),
+            (Some(min), None) => Some(format!("{min}+")),
+            (None, Some(max)) => Some(format!("up to {max}")),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Dimensions {
+    const CENTIMETERS_PER_INCH: f64 = 2.54;
+    const OUNCES_PER_KILOGRAM: f64 = 35.27396195;
+
+    /// Convert the dimensions to imperial units. BrickSet reports lengths in centimeters and
+    /// weight in kilograms; the result has lengths in inches and weight in ounces.
+    pub fn to_imperial(&self) -> Dimensions {
+        Dimensions {
+            height: self.height.map(|cm| cm / Self::CENTIMETERS_PER_INCH),
+            width: self.width.map(|cm| cm / Self::CENTIMETERS_PER_INCH),
+            depth: self.depth.map(|cm| cm / Self::CENTIMETERS_PER_INCH),
+            weight: self.weight.map(|kg| kg * Self::OUNCES_PER_KILOGRAM),
+        }
+    }
+
+    /// The volume of the box in cubic centimeters, or `None` if any length is missing.
+    pub fn volume_cm3(&self) -> Option<f64> {
+        Some(self.height? * self.width? * self.depth?)
+    }
+}
+
+impl Barcode {
+    /// [`Self::upc`] with all non-digit characters removed. Returns `None` if the result
+    /// isn't a 12-digit UPC-A code with a valid check digit.
+    pub fn normalized_upc(&self) -> Option<String> {
+        normalize_gtin(self.upc.as_deref()?, 12)
+    }
+
+    /// [`Self::ean`] with all non-digit characters removed. Returns `None` if the result
+    /// isn't a 13-digit EAN-13 code with a valid check digit.
+    pub fn normalized_ean(&self) -> Option<String> {
+        normalize_gtin(self.ean.as_deref()?, 13)
+    }
+}
+
+/// Strip non-digits from a UPC or EAN code, then check its length and check digit.
+fn normalize_gtin(code: &str, len: usize) -> Option<String> {
+    let digits: String = code.chars().filter(char::is_ascii_digit).collect();
+    if digits.len() != len {
+        return None;
+    }
+
+    let values: Vec<u32> = digits.bytes().map(|b| (b - b'0') as u32).collect();
+    let (check, payload) = values.split_last()?;
+    // Weights alternate 3, 1, 3, ... starting from the digit next to the check digit.
+    let sum: u32 = payload.iter().rev().zip([3, 1].into_iter().cycle()).map(|(d, w)| d * w).sum();
+
+    if (10 - sum % 10) % 10 == *check {
+        Some(digits)
+    } else {
+        None
+    }
+}
+
+impl ExtendedData {
+    /// Returns true if [`Self::tags`] contains `tag`, ignoring ASCII case. Tags are only
+    /// included in responses when [`crate::request::GetSetsParameters::extended_data`] is set.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+impl Collections {
+    /// The number of users who want this set, divided by the number of users who own it.
+    /// Returns `None` if either count is missing, or if nobody owns the set.
+    pub fn want_to_own_ratio(&self) -> Option<f64> {
+        match (self.wanted_by, self.owned_by) {
+            (Some(wanted_by), Some(owned_by)) if owned_by > 0 => Some(wanted_by as f64 / owned_by as f64),
+            _ => None,
+        }
+    }
+
+    /// The number of users who own or want this set. Returns `None` if either count is missing.
+    pub fn popularity(&self) -> Option<usize> {
+        Some(self.owned_by? + self.wanted_by?)
+    }
+}
+
+impl Image {
+    /// Parse [`Self::thumbnail_url`].
+    pub fn thumbnail(&self) -> Option<Result<Url, url::ParseError>> {
+        self.thumbnail_url.as_deref().map(Url::parse)
+    }
+
+    /// Parse [`Self::image_url`].
+    pub fn image(&self) -> Option<Result<Url, url::ParseError>> {
+        self.image_url.as_deref().map(Url::parse)
+    }
+
+    /// The full-size image URL if there is one, otherwise the thumbnail URL.
+    pub fn best(&self) -> Option<&str> {
+        self.image_url.as_deref().or(self.thumbnail_url.as_deref())
+    }
+}
+
+impl Instructions {
+    /// Parse [`Self::url`].
+    pub fn parsed_url(&self) -> Result<Url, url::ParseError> {
+        Url::parse(&self.url)
+    }
+
+    /// The file extension of the instructions file, e.g. `"pdf"`, taken from the path of
+    /// [`Self::url`]. The extension isn't converted to lowercase.
+    pub fn file_extension(&self) -> Option<&str> {
+        let rest = self.url.split_once("://").map_or(self.url.as_str(), |(_, rest)| rest);
+        let rest = rest.split(['?', '#']).next()?;
+        let path = &rest[rest.find('/')?..];
+        let file_name = path.rsplit('/').next()?;
+        match file_name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() && !extension.is_empty() => Some(extension),
+            _ => None,
+        }
+    }
+}
+
+impl Review {
+    /// The review's body as plain text. If [`Self::html`] is true, HTML tags are removed,
+    /// `<br>` tags become line breaks, and character entities like `&amp;` are decoded.
+    /// Otherwise, the body is returned unchanged.
+    pub fn plain_text(&self) -> String {
+        if !self.html {
+            return self.review.clone();
+        }
+
+        let mut text = String::with_capacity(self.review.len());
+        let mut rest = self.review.as_str();
+        while let Some(i) = rest.find(['<', '&']) {
+            text.push_str(&rest[..i]);
+            rest = &rest[i..];
+
+            if rest.starts_with('<') {
+                let Some(end) = rest.find('>') else {
+                    break;
+                };
+                let tag = rest[1..end].trim().trim_end_matches('/').trim_end();
+                if tag.eq_ignore_ascii_case("br") {
+                    text.push('\n');
+                }
+                rest = &rest[end + 1..];
+            } else {
+                match rest.find(';').and_then(|end| Some((decode_entity(&rest[1..end])?, end))) {
+                    Some((c, end)) => {
+                        text.push(c);
+                        rest = &rest[end + 1..];
+                    }
+                    None => {
+                        text.push('&');
+                        rest = &rest[1..];
+                    }
+                }
+            }
+        }
+        text.push_str(rest);
+        text
+    }
+}
+
+/// Decode an HTML character entity, without the surrounding `&` and `;`.
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            let code = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+impl Rating {
+    /// Returns true if the reviewer scored every aspect of the set.
+    pub fn is_complete(&self) -> bool {
+        self.sub_scores().all(|score| score.is_some())
+    }
+
+    /// The average of the aspects the reviewer scored, ignoring [`Self::overall`]. Returns
+    /// `None` if the reviewer didn't score any aspects.
+    pub fn average(&self) -> Option<f64> {
+        let scores: Vec<i32> = self.sub_scores().flatten().collect();
+        if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<i32>() as f64 / scores.len() as f64)
+        }
+    }
+
+    fn sub_scores(&self) -> impl Iterator<Item = Option<i32>> {
+        [self.parts, self.building_experience, self.playability, self.value_for_money].into_iter()
+    }
+}
+
+impl GetMinifigCollectionResponse {
+    /// The minifigs in a particular category or any of its subcategories, ignoring ASCII case.
+    /// Categories are paths separated by `/`, so `"Star Wars"` matches both `"Star Wars"` and
+    /// `"Star Wars / Star Wars Episode 1"`, but not `"Star Wars Episode 1"`. The BrickSet API
+    /// can't filter minifigs by category, so this filtering is done client-side, after the
+    /// whole collection has been retrieved.
+    pub fn filter_by_category(&self, category: &str) -> Vec<&MinifigCollection> {
+        let wanted: Vec<&str> = category.split('/').map(str::trim).collect();
+        self.minifigs.iter()
+            .filter(|minifig| {
+                let mut segments = minifig.category.split('/').map(str::trim);
+                wanted.iter().all(|want| segments.next().is_some_and(|segment| segment.eq_ignore_ascii_case(want)))
+            })
+            .collect()
+    }
+
+    /// The minifigs the user owns at least one of.
+    pub fn owned_only(&self) -> Vec<&MinifigCollection> {
+        self.minifigs.iter()
+            .filter(|minifig| minifig.owned_total > 0)
+            .collect()
+    }
+
+    /// The minifigs on the user's wanted list.
+    pub fn wanted_only(&self) -> Vec<&MinifigCollection> {
+        self.minifigs.iter()
+            .filter(|minifig| minifig.wanted)
+            .collect()
+    }
+
+    /// The minifigs, sorted by name, then by minifig number.
+    pub fn sorted_by_name(&self) -> Vec<&MinifigCollection> {
+        let mut sorted: Vec<&MinifigCollection> = self.minifigs.iter().collect();
+        sorted.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.minifig_number.cmp(&b.minifig_number)));
+        sorted
+    }
+}
+
+impl MinifigCollection {
+    /// [`Self::owned_in_sets`] plus [`Self::owned_loose`].
+    pub fn computed_total(&self) -> usize {
+        self.owned_in_sets + self.owned_loose
+    }
+
+    /// Returns true if [`Self::owned_total`] matches [`Self::computed_total`]. BrickSet
+    /// occasionally reports totals that don't add up.
+    pub fn is_consistent(&self) -> bool {
+        self.computed_total() == self.owned_total
+    }
+}
+
+impl LegoCom {
+    /// Get the LEGO.com details for a particular region.
+    pub fn get(&self, region: Region) -> &LegoComDetails {
+        match region {
+            Region::US => &self.united_states,
+            Region::UK => &self.united_kingdom,
+            Region::CA => &self.canada,
+            Region::DE => &self.germany,
+            Region::FR => &self.france,
+        }
+    }
+
+    /// Iterate over the LEGO.com details for every region.
+    pub fn iter(&self) -> impl Iterator<Item = (Region, &LegoComDetails)> {
+        Region::ALL.iter().map(|&region| (region, self.get(region)))
+    }
+}
+
+impl Region {
+    /// Every [`Region`].
+    pub const ALL: &'static [Region] = &[
+        Region::US,
+        Region::UK,
+        Region::CA,
+        Region::DE,
+        Region::FR,
+    ];
+
+    /// The ISO 4217 code of the currency used for retail prices in this region.
+    pub fn currency_code(self) -> &'static str {
+        match self {
+            Region::US => "USD",
+            Region::UK => "GBP",
+            Region::CA => "CAD",
+            Region::DE => "EUR",
+            Region::FR => "EUR",
+        }
+    }
+}
+
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Region::US => "US",
+            Region::UK => "UK",
+            Region::CA => "CA",
+            Region::DE => "DE",
+            Region::FR => "FR",
+        })
+    }
+}
+
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,139 @@
+//! Canonical BrickSet API responses, for testing code that handles BrickSet data without
+//! a network connection. Enabled by the `test-fixtures` feature.
+//!
+//! Each fixture is available as raw JSON, exactly as BrickSet would send it, and as a
+//! parsed response.
+
+use super::{Error, GetSetsResponse, GetThemesResponse};
+use crate::Response;
+
+/// A successful `getSets` response containing two sets, with extended data.
+pub const GET_SETS: &str = r#"{
+    "status": "success",
+    "matches": 2,
+    "sets": [
+        {
+            "setID": 31278,
+            "number": "10497",
+            "numberVariant": 1,
+            "name": "Galaxy Explorer",
+            "year": 2022,
+            "theme": "Icons",
+            "themeGroup": "Model making",
+            "subtheme": "Space",
+            "category": "Normal",
+            "released": true,
+            "pieces": 1254,
+            "minifigs": 4,
+            "image": {
+                "thumbnailURL": "https://images.brickset.com/sets/small/10497-1.jpg",
+                "imageURL": "https://images.brickset.com/sets/images/10497-1.jpg"
+            },
+            "bricksetURL": "https://brickset.com/sets/10497-1",
+            "collection": {},
+            "collections": { "ownedBy": 21523, "wantedBy": 6388 },
+            "LEGOCom": {
+                "US": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+                "UK": { "retailPrice": 89.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+                "CA": { "retailPrice": 129.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+                "DE": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" },
+                "FR": { "retailPrice": 99.99, "dateFirstAvailable": "2022-08-01T00:00:00Z" }
+            },
+            "rating": 4.6,
+            "reviewCount": 12,
+            "packagingType": "Box",
+            "availability": "LEGO exclusive",
+            "instructionsCount": 2,
+            "additionalImageCount": 14,
+            "ageRange": { "min": 18 },
+            "dimensions": { "height": 37.8, "width": 47.9, "depth": 9.4, "weight": 1.945 },
+            "barcode": { "EAN": "5702017152738", "UPC": "673419363741" },
+            "extendedData": { "tags": ["Spaceship", "Classic Space", "D2C"] },
+            "lastUpdated": "2023-06-01T09:13:44.553Z"
+        },
+        {
+            "setID": 26725,
+            "number": "6876",
+            "numberVariant": 1,
+            "name": "Mini Mobile Space Command",
+            "year": 1986,
+            "theme": "Space",
+            "themeGroup": "Action/Adventure",
+            "subtheme": "{Not specified}",
+            "category": "Normal",
+            "released": true,
+            "pieces": 41,
+            "minifigs": 1,
+            "image": {
+                "thumbnailURL": "https://images.brickset.com/sets/small/6876-1.jpg",
+                "imageURL": "https://images.brickset.com/sets/images/6876-1.jpg"
+            },
+            "bricksetURL": "https://brickset.com/sets/6876-1",
+            "collection": {},
+            "collections": { "ownedBy": 1523, "wantedBy": 127 },
+            "LEGOCom": {
+                "US": {},
+                "UK": {},
+                "CA": {},
+                "DE": {}
+            },
+            "rating": 0,
+            "reviewCount": 0,
+            "packagingType": "{Not specified}",
+            "availability": "{Not specified}",
+            "instructionsCount": 1,
+            "additionalImageCount": 0,
+            "ageRange": {},
+            "dimensions": {},
+            "barcode": {},
+            "extendedData": {},
+            "lastUpdated": "2021-03-14T18:02:51.12Z"
+        }
+    ]
+}"#;
+
+/// A successful `getThemes` response containing three themes.
+pub const GET_THEMES: &str = r#"{
+    "status": "success",
+    "matches": 3,
+    "themes": [
+        { "theme": "Castle", "setCount": 340, "subthemeCount": 24, "yearFrom": 1978, "yearTo": 2023 },
+        { "theme": "Icons", "setCount": 78, "subthemeCount": 12, "yearFrom": 2021, "yearTo": 2023 },
+        { "theme": "Space", "setCount": 812, "subthemeCount": 31, "yearFrom": 1978, "yearTo": 2023 }
+    ]
+}"#;
+
+/// The error response BrickSet sends when the API key is invalid.
+pub const ERROR: &str = r#"{
+    "status": "error",
+    "message": "Invalid API key"
+}"#;
+
+/// [`GET_SETS`], parsed.
+pub fn example_get_sets() -> GetSetsResponse {
+    parse_ok("GET_SETS", GET_SETS)
+}
+
+/// [`GET_THEMES`], parsed.
+pub fn example_get_themes() -> GetThemesResponse {
+    parse_ok("GET_THEMES", GET_THEMES)
+}
+
+/// [`ERROR`], parsed.
+pub fn example_error() -> Error {
+    let response = serde_json::from_str::<Response<GetThemesResponse>>(ERROR)
+        .expect("ERROR fixture failed to parse");
+    match response {
+        Response::Err(err) => err,
+        Response::Ok(_) => panic!("ERROR fixture is not an error response"),
+    }
+}
+
+fn parse_ok<T: serde::de::DeserializeOwned>(name: &str, json: &str) -> T {
+    let response = serde_json::from_str::<Response<T>>(json)
+        .unwrap_or_else(|err| panic!("{name} fixture failed to parse: {err}"));
+    match response {
+        Response::Ok(ok) => ok,
+        Response::Err(err) => panic!("{name} fixture is an error response: {err}"),
+    }
+}
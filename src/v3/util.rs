@@ -13,18 +13,55 @@ impl Serialize for Flag {
 }
 
 impl<'de> Deserialize<'de> for Flag {
+    /// Accepts `1`, `"1"` and `true`, since BrickSet isn't consistent about how it
+    /// represents flags.
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        use serde::de::Error;
+        struct FlagVisitor;
 
-        let i = i32::deserialize(deserializer)?;
-        if i == 1 {
-            Ok(Flag)
-        } else {
-            Err(D::Error::custom(format!("flag must be 1, was {i}")))
+        impl serde::de::Visitor<'_> for FlagVisitor {
+            type Value = Flag;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("1, \"1\" or true")
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Flag, E> {
+                if v == 1 {
+                    Ok(Flag)
+                } else {
+                    Err(E::custom(format!("flag must be 1, was {v}")))
+                }
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Flag, E> {
+                if v == 1 {
+                    Ok(Flag)
+                } else {
+                    Err(E::custom(format!("flag must be 1, was {v}")))
+                }
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Flag, E> {
+                if v == "1" {
+                    Ok(Flag)
+                } else {
+                    Err(E::custom(format!("flag must be \"1\", was {v:?}")))
+                }
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Flag, E> {
+                if v {
+                    Ok(Flag)
+                } else {
+                    Err(E::custom("flag must be true, was false"))
+                }
+            }
         }
+
+        deserializer.deserialize_any(FlagVisitor)
     }
 }
 
@@ -39,7 +76,10 @@ pub(crate) mod updated_since_format {
     where
         S: Serializer
     {
-        date.unwrap().format(FMT).to_string().serialize(serializer)
+        match date {
+            Some(date) => date.format(FMT).to_string().serialize(serializer),
+            None => serializer.serialize_none(),
+        }
     }
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
@@ -48,10 +88,12 @@ pub(crate) mod updated_since_format {
     {
         use serde::de::Error;
 
-        let text = String::deserialize(deserializer)?;
-        match NaiveDate::parse_from_str(&text, FMT) {
-            Ok(date) => Ok(Some(date)),
-            Err(err) => Err(D::Error::custom(format!("{err}"))),
+        match Option::<String>::deserialize(deserializer)? {
+            Some(text) => match NaiveDate::parse_from_str(&text, FMT) {
+                Ok(date) => Ok(Some(date)),
+                Err(err) => Err(D::Error::custom(format!("{err}"))),
+            },
+            None => Ok(None),
         }
     }
 }
@@ -90,7 +132,7 @@ pub(crate) mod not_specified_optional_string {
 pub(crate) mod int_vec_as_commastr {
     use serde::{self, Deserialize, Serializer, Deserializer, Serialize};
 
-    pub fn serialize<S>(years: &Vec<i32>, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S>(years: &[i32], serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer
     {
@@ -116,7 +158,7 @@ pub(crate) mod int_vec_as_commastr {
 
         match StringOrInt::deserialize(deserializer)? {
             StringOrInt::String(str) => {
-                for i in str.split(",").map(|s| i32::from_str_radix(s.trim(), 10)) {
+                for i in str.split(",").map(|s| s.trim().parse::<i32>()) {
                     match i {
                         Ok(i) => result.push(i),
                         Err(err) => return Err(D::Error::custom(format!("{err}")))
@@ -132,30 +174,146 @@ pub(crate) mod int_vec_as_commastr {
     }
 }
 
-/// Deserializes a nullable `i32` normally, except zero is mapped to None.
+/// Converts a [`Vec<&str>`] to a comma-delimited string, and vice versa.
+pub(crate) mod str_vec_as_commastr {
+    use serde::{self, Deserializer, Serializer, Serialize};
+    use std::borrow::Cow;
+
+    pub fn serialize<S>(strs: &[Cow<str>], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        use itertools::Itertools;
+
+        format!("{}", strs.iter().format(", ")).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Cow<'de, str>>, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        struct CommaStrVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for CommaStrVisitor {
+            type Value = Vec<Cow<'de, str>>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a comma-separated string")
+            }
+
+            fn visit_borrowed_str<E: serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(v.split(',').map(|s| Cow::Borrowed(s.trim())).collect())
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(v.split(',').map(|s| Cow::Owned(s.trim().to_string())).collect())
+            }
+        }
+
+        deserializer.deserialize_str(CommaStrVisitor)
+    }
+}
+
+/// Deserializes a number normally, except zero is mapped to None.
 pub(crate) mod zero_none {
     use serde::{self, Deserialize, Serializer, Deserializer, Serialize};
 
-    pub fn serialize<S>(value: &Option<i32>, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
     where
-        S: Serializer
+        S: Serializer,
+        T: Serialize + Default,
     {
         match value {
-            None => 0.serialize(serializer),
+            None => T::default().serialize(serializer),
             Some(val) => val.serialize(serializer),
         }
     }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
     where
-        D: Deserializer<'de>
+        D: Deserializer<'de>,
+        T: Deserialize<'de> + Default + PartialEq,
     {
-        let value = i32::deserialize(deserializer)?;
+        let value = T::deserialize(deserializer)?;
 
-        if value == 0 {
+        if value == T::default() {
             Ok(None)
         } else {
             Ok(Some(value))
         }
     }
 }
+
+/// Deserializes an `Option<f64>` from either a JSON number or a numeric string like
+/// `"59.99"`, since BrickSet isn't consistent about which one it sends. An empty string is
+/// mapped to None. Serializes as a plain number.
+pub(crate) mod lenient_f64 {
+    use serde::{self, de, Serializer, Deserializer, Serialize};
+
+    pub fn serialize<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer
+    {
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        deserializer.deserialize_any(LenientF64Visitor)
+    }
+
+    /// Same as [`deserialize`], except zero is mapped to None, like [`super::zero_none`].
+    pub fn deserialize_zero_none<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>
+    {
+        Ok(deserialize(deserializer)?.filter(|value| *value != 0.0))
+    }
+
+    struct LenientF64Visitor;
+
+    impl<'de> de::Visitor<'de> for LenientF64Visitor {
+        type Value = Option<f64>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a finite number, a numeric string, or null")
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Ok(Some(v))
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            let v = v.trim();
+            if v.is_empty() {
+                return Ok(None);
+            }
+            match v.parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(Some(value)),
+                _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+}